@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::constants::{MAX_PLAYERS, ROUNDS};
+use crate::game_states::base_game_state::GameState;
+use crate::structs::{Action, ActionIdentifier, ActionType, BetSizing};
+
+/*
+    The action abstraction decides which bet sizes are available in every situation, and which ActionIdentifier every action has.
+    It is loaded from a TOML or JSON file, see action_abstractions/default.toml for the format:
+        bet_sizes: every bet size that gets an ActionIdentifier, in identifier order. When left out all bet sizes of the rounds are used, sorted by sizing and size
+        rounds: per round the bet sizes per raise depth (0 = the first bet of the round, 1 = a raise, 2 = a three-bet, ...)
+        rounds.positions: optional bet sizes per raise depth for a single seat (0 = small blind), these replace the bet sizes of the round
+        all_in_threshold: optional percentage, bets that move more than this part of the remaining stack into the pot are left out in favour of going all-in
+        merge_threshold: optional percentage, a bet that is at most this much larger than a smaller bet that is available (0 = the same amount of chips) is left out
+    A bet size is either a number, a pot multiplier in hundredths like Action.raise_amount, or a string in any sizing: "x0.75" (pot), "2.5bb", "3x" (last raise) or "250c" (chips).
+    When the raise depth is past the last configured one, only folding, calling and going all-in are available.
+
+    ActionIdentifiers: 0 = fold, 1 = call, 2 = all-in, 3 = the fixed size bet of Kuhn and Leduc poker, bet_sizes[i] = i + 4.
+*/
+
+const FIXED_ACTION_AMOUNT: usize = 4;
+
+lazy_static! {
+    pub static ref DEFAULT_ACTION_ABSTRACTION: ActionAbstraction = {
+        return ActionAbstraction::from_toml(include_str!("../action_abstractions/default.toml")).unwrap();
+    };
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum BetSizeRepresentation {
+    PotFraction(u16),
+    Text(String),
+}
+
+#[derive(Eq, Hash, Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "BetSizeRepresentation", into = "BetSizeRepresentation")]
+pub struct BetSize {
+    pub sizing: BetSizing,
+    pub raise_amount: u16,
+}
+
+impl BetSize {
+    pub fn pot_fraction(raise_amount: u16) -> Self {
+        return BetSize { sizing: BetSizing::PotFraction, raise_amount }
+    }
+
+    pub fn into_action(self) -> Action {
+        return Action { action_type: ActionType::Bet, raise_amount: self.raise_amount, sizing: self.sizing }
+    }
+}
+
+impl TryFrom<BetSizeRepresentation> for BetSize {
+    type Error = String;
+
+    fn try_from(representation: BetSizeRepresentation) -> Result<Self, Self::Error> {
+        match representation {
+            BetSizeRepresentation::PotFraction(raise_amount) => return Ok(BetSize::pot_fraction(raise_amount)),
+            BetSizeRepresentation::Text(text) => {
+                let (sizing, raise_amount) = BetSizing::parse_amount(&text).ok_or(format!("invalid bet size \"{}\"", text))?;
+                return Ok(BetSize { sizing, raise_amount })
+            },
+        }
+    }
+}
+
+impl From<BetSize> for BetSizeRepresentation {
+    fn from(bet_size: BetSize) -> Self {
+        if bet_size.sizing == BetSizing::PotFraction {
+            return BetSizeRepresentation::PotFraction(bet_size.raise_amount)
+        }
+        return BetSizeRepresentation::Text(bet_size.sizing.format_amount(bet_size.raise_amount))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionAbstractionConfig {
+    pub player_index: usize,
+    pub raise_depths: Vec<Vec<BetSize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundAbstractionConfig {
+    pub raise_depths: Vec<Vec<BetSize>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub positions: Vec<PositionAbstractionConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionAbstractionConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bet_sizes: Vec<BetSize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_in_threshold: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_threshold: Option<u16>,
+    pub rounds: Vec<RoundAbstractionConfig>,
+}
+
+// Bets per raise depth
+type RaiseDepthBets = Vec<SmallVec<[Action; 40]>>;
+
+#[derive(Clone, Debug)]
+pub struct ActionAbstraction {
+    pub config: ActionAbstractionConfig,
+    pub(crate) identifier_to_action: Vec<Action>,
+    pub(crate) action_to_identifier: HashMap<Action, ActionIdentifier>,
+    // [round][raise depth]
+    bets: Vec<RaiseDepthBets>,
+    // [round][player index][raise depth], None if the seat uses the bets of the round
+    position_bets: Vec<[Option<RaiseDepthBets>; MAX_PLAYERS]>,
+}
+
+fn to_bets(raise_depths: &[Vec<BetSize>]) -> RaiseDepthBets {
+    return raise_depths.iter().map(|bet_sizes| {
+        return bet_sizes.iter().map(|bet_size| bet_size.into_action()).collect()
+    }).collect()
+}
+
+impl ActionAbstraction {
+    pub fn new(mut config: ActionAbstractionConfig) -> Result<Self, String> {
+        if config.rounds.is_empty() || config.rounds.len() > ROUNDS {
+            return Err(format!("expected 1 to {} rounds, got {}", ROUNDS, config.rounds.len()));
+        }
+
+        let mut all_raise_depths = Vec::new();
+        for (round, round_config) in config.rounds.iter().enumerate() {
+            all_raise_depths.push(&round_config.raise_depths);
+            for position_config in round_config.positions.iter() {
+                if position_config.player_index >= MAX_PLAYERS {
+                    return Err(format!("round {} has bet sizes for invalid player index {}", round, position_config.player_index));
+                }
+                if round_config.positions.iter().filter(|other| other.player_index == position_config.player_index).count() > 1 {
+                    return Err(format!("round {} has bet sizes for player {} more than once", round, position_config.player_index));
+                }
+                all_raise_depths.push(&position_config.raise_depths);
+            }
+        }
+        let round_bet_sizes = all_raise_depths.into_iter().flatten().flatten().copied().collect::<Vec<BetSize>>();
+        if round_bet_sizes.iter().any(|bet_size| bet_size.raise_amount == 0) {
+            return Err("bet sizes have to be larger than 0".to_owned());
+        }
+
+        if config.all_in_threshold.is_some_and(|all_in_threshold| all_in_threshold == 0 || all_in_threshold > 100) {
+            return Err("all_in_threshold has to be a percentage between 1 and 100".to_owned());
+        }
+
+        if config.bet_sizes.is_empty() {
+            config.bet_sizes = round_bet_sizes.clone();
+            config.bet_sizes.sort();
+            config.bet_sizes.dedup();
+        } else {
+            if let Some(bet_size) = round_bet_sizes.iter().find(|bet_size| !config.bet_sizes.contains(bet_size)) {
+                return Err(format!("bet size {} is missing from bet_sizes", bet_size.sizing.format_amount(bet_size.raise_amount)));
+            }
+            if config.bet_sizes.iter().enumerate().any(|(index, bet_size)| bet_size.raise_amount == 0 || config.bet_sizes[..index].contains(bet_size)) {
+                return Err("bet_sizes has to contain unique bet sizes larger than 0".to_owned());
+            }
+        }
+        if config.bet_sizes.len() + FIXED_ACTION_AMOUNT > ActionIdentifier::MAX as usize + 1 {
+            return Err(format!("there can be at most {} bet sizes", ActionIdentifier::MAX as usize + 1 - FIXED_ACTION_AMOUNT));
+        }
+
+        let mut identifier_to_action = vec![
+            Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction },
+        ];
+        identifier_to_action.extend(config.bet_sizes.iter().map(|bet_size| bet_size.into_action()));
+        let action_to_identifier = identifier_to_action.iter().enumerate()
+            .map(|(action_identifier, &action)| (action, action_identifier as ActionIdentifier))
+            .collect();
+
+        let bets = config.rounds.iter().map(|round_config| to_bets(&round_config.raise_depths)).collect();
+        let position_bets = config.rounds.iter().map(|round_config| {
+            let mut position_bets: [Option<RaiseDepthBets>; MAX_PLAYERS] = Default::default();
+            for position_config in round_config.positions.iter() {
+                position_bets[position_config.player_index] = Some(to_bets(&position_config.raise_depths));
+            }
+            return position_bets
+        }).collect();
+
+        return Ok(ActionAbstraction { config, identifier_to_action, action_to_identifier, bets, position_bets })
+    }
+
+    pub fn from_toml(toml_string: &str) -> Result<Self, String> {
+        let config = toml::from_str::<ActionAbstractionConfig>(toml_string).map_err(|error| format!("invalid action abstraction toml: {}", error))?;
+        return ActionAbstraction::new(config)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config = serde_json::from_str::<ActionAbstractionConfig>(json).map_err(|error| format!("invalid action abstraction json: {}", error))?;
+        return ActionAbstraction::new(config)
+    }
+
+    // Loads a .json file as JSON and anything else as TOML
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+        if path.extension().is_some_and(|extension| extension == "json") {
+            return ActionAbstraction::from_json(&contents)
+        }
+        return ActionAbstraction::from_toml(&contents)
+    }
+
+    pub fn to_toml(&self) -> String {
+        return toml::to_string(&self.config).unwrap()
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(&self.config).unwrap()
+    }
+
+    pub fn action_amount(&self) -> usize {
+        return self.identifier_to_action.len()
+    }
+
+    pub fn get_action(&self, action_identifier: ActionIdentifier) -> Option<Action> {
+        return self.identifier_to_action.get(action_identifier as usize).copied()
+    }
+
+    pub fn get_identifier(&self, action: &Action) -> Option<ActionIdentifier> {
+        return self.action_to_identifier.get(&action.without_check_raise()).copied()
+    }
+
+    pub fn get_bets(&self, round: usize, raise_depth: usize, player_index: usize) -> Option<&SmallVec<[Action; 40]>> {
+        let round_position_bets = self.position_bets.get(round)?;
+        if let Some(Some(bets)) = round_position_bets.get(player_index) {
+            return bets.get(raise_depth)
+        }
+        return self.bets[round].get(raise_depth)
+    }
+
+    pub fn get_bets_in_abstraction<T: GameState>(&self, game_state: &T) -> Option<&SmallVec<[Action; 40]>> {
+        return self.get_bets(game_state.get_current_round_index(), game_state.get_current_bet_count(), game_state.get_active_player_index())
+    }
+
+    /*
+        The actions of the active player that are both in the abstraction and legal.
+        Bets over the all-in threshold and bets that merge into a smaller bet are left out, this only applies to games where bets have a size in chips.
+    */
+    pub fn get_active_player_actions<T: GameState>(&self, game_state: &T) -> SmallVec<[Action; 40]> {
+        let mut actions = game_state.get_active_player_actions(self.get_bets_in_abstraction(game_state));
+        if self.config.all_in_threshold.is_none() && self.config.merge_threshold.is_none() {
+            return actions
+        }
+
+        let mut bet_amounts = actions.iter()
+            .filter(|action| action.is_bet_raise())
+            .filter_map(|action| game_state.get_bet_commitment(action))
+            .filter(|&(extra_bets, stack)| {
+                return self.config.all_in_threshold.is_none_or(|all_in_threshold| extra_bets as u64 * 100 <= stack as u64 * all_in_threshold as u64)
+            })
+            .map(|(extra_bets, _)| extra_bets)
+            .collect::<Vec<u32>>();
+        bet_amounts.sort();
+        if let Some(merge_threshold) = self.config.merge_threshold {
+            // Keep the smallest bet of every group of bets that are close together
+            let mut kept_bet_amount: Option<u32> = None;
+            bet_amounts.retain(|&extra_bets| {
+                if kept_bet_amount.is_some_and(|kept| extra_bets as u64 * 100 <= kept as u64 * (100 + merge_threshold as u64)) {
+                    return false
+                }
+                kept_bet_amount = Some(extra_bets);
+                return true
+            });
+        }
+
+        // When several bets resolve to the kept amount, the first one in the abstraction stays
+        actions.retain(|action| {
+            if !action.is_bet_raise() {
+                return true
+            }
+            let Some((extra_bets, _)) = game_state.get_bet_commitment(action) else {
+                return true
+            };
+            if let Ok(index) = bet_amounts.binary_search(&extra_bets) {
+                bet_amounts.remove(index);
+                return true
+            }
+            return false
+        });
+        return actions
+    }
+}
+
+impl Default for ActionAbstraction {
+    fn default() -> Self {
+        return DEFAULT_ACTION_ABSTRACTION.clone()
+    }
+}
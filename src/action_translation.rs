@@ -0,0 +1,121 @@
+use rand::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
+use smallvec::SmallVec;
+
+use crate::action_abstraction::ActionAbstraction;
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::nlth_poker::game_state::{resolve_bet_amount, NLTHGameState};
+use crate::structs::{Action, ActionType, BetSizing};
+
+/*
+    Action translation maps a bet of the real game that is not in the action abstraction ("off-tree") onto the bets that are.
+    Bet sizes are compared as a fraction of the pot after calling, x = (extra bets - call amount) / (pot + call amount).
+    When x lies between two abstract bet sizes A < x < B:
+        PseudoHarmonic: A with probability f(x) = ((B - x)(1 + A)) / ((B - A)(1 + x)) and B otherwise (Ganzfried & Sandholm, 2013)
+        DeterministicPseudoHarmonic: A if f(x) >= 0.5, otherwise B
+        NearestNeighbour: the closest of A and B, A when they are equally close
+    Bets below the smallest or above the largest abstract bet size map onto that bet size. Calls and all-ins are never translated.
+
+    A raise is only ever translated to a raise, so every translated state is a valid continuation of the real game:
+    its history contains the abstract action, while the pot, stacks and minimum raise come from the real amount.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranslationMethod {
+    PseudoHarmonic,
+    DeterministicPseudoHarmonic,
+    NearestNeighbour,
+}
+
+#[derive(Clone, Debug)]
+pub struct TranslatedAction {
+    pub action: Action,
+    pub probability: f32,
+    pub translated_state: NLTHGameState,
+}
+
+pub fn pseudo_harmonic_probability(a: f32, b: f32, x: f32) -> f32 {
+    return ((b - x) * (1.0 + a)) / ((b - a) * (1.0 + x))
+}
+
+// `extra_bets` is the amount the active player moves from their stack into the pot, including the call amount
+pub fn translate_bet(
+    game_state: &NLTHGameState,
+    extra_bets: u32,
+    action_abstraction: &ActionAbstraction,
+    method: TranslationMethod,
+) -> Result<SmallVec<[TranslatedAction; 2]>, String> {
+    if game_state.is_terminal() {
+        return Err("cannot translate a bet in a terminal state".to_owned());
+    }
+    let active_player_index = game_state.active_player_index;
+    let stack = game_state.stacks[active_player_index];
+    let call_amount = game_state.get_call_amount();
+    if extra_bets > stack {
+        return Err(format!("player {} cannot bet {} with a stack of {}", active_player_index, extra_bets, stack));
+    }
+
+    let exact = |action: Action| {
+        return TranslatedAction { action, probability: 1.0, translated_state: game_state.handle_action(action) }
+    };
+    if extra_bets == stack {
+        return Ok(SmallVec::from_elem(exact(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }), 1));
+    }
+    if extra_bets == call_amount {
+        return Ok(SmallVec::from_elem(exact(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }), 1));
+    }
+    if extra_bets < call_amount || extra_bets - call_amount < game_state.minimum_raise_amount {
+        return Err(format!("{} is not a legal bet, the call amount is {} and the minimum raise is {}", extra_bets, call_amount, game_state.minimum_raise_amount));
+    }
+
+    // The legal abstract bets with their real amount, from small to large
+    let pot_after_call = (game_state.get_total_pot() + call_amount) as f32;
+    let mut abstract_bets = action_abstraction.get_active_player_actions(game_state).into_iter()
+        .filter(|action| action.is_bet_raise())
+        .map(|action| (action, resolve_bet_amount(game_state, &action)))
+        .collect::<Vec<(Action, u32)>>();
+    abstract_bets.sort_by_key(|&(_, abstract_extra_bets)| abstract_extra_bets);
+    abstract_bets.dedup_by_key(|&mut (_, abstract_extra_bets)| abstract_extra_bets);
+    if abstract_bets.is_empty() {
+        return Err("there are no abstract bets to translate to".to_owned());
+    }
+
+    let translate = |(action, _): (Action, u32), probability: f32| {
+        return TranslatedAction { action, probability, translated_state: game_state.handle_translated_bet(action, extra_bets) }
+    };
+    let pot_fraction = |amount: u32| (amount - call_amount) as f32 / pot_after_call;
+
+    let upper_index = abstract_bets.partition_point(|&(_, abstract_extra_bets)| abstract_extra_bets < extra_bets);
+    if upper_index == abstract_bets.len() {
+        return Ok(SmallVec::from_elem(translate(abstract_bets[upper_index - 1], 1.0), 1));
+    }
+    if upper_index == 0 || abstract_bets[upper_index].1 == extra_bets {
+        return Ok(SmallVec::from_elem(translate(abstract_bets[upper_index], 1.0), 1));
+    }
+
+    let lower = abstract_bets[upper_index - 1];
+    let upper = abstract_bets[upper_index];
+    let (a, b, x) = (pot_fraction(lower.1), pot_fraction(upper.1), pot_fraction(extra_bets));
+    let lower_probability = pseudo_harmonic_probability(a, b, x);
+
+    let mut translated_actions = SmallVec::new();
+    match method {
+        TranslationMethod::PseudoHarmonic => {
+            translated_actions.push(translate(lower, lower_probability));
+            translated_actions.push(translate(upper, 1.0 - lower_probability));
+        },
+        TranslationMethod::DeterministicPseudoHarmonic => {
+            translated_actions.push(translate(if lower_probability >= 0.5 { lower } else { upper }, 1.0));
+        },
+        TranslationMethod::NearestNeighbour => {
+            translated_actions.push(translate(if x - a <= b - x { lower } else { upper }, 1.0));
+        },
+    }
+    return Ok(translated_actions)
+}
+
+// Picks one of the translated actions according to their probabilities
+pub fn sample_translation<'a, R: Rng>(translated_actions: &'a [TranslatedAction], rng: &mut R) -> &'a TranslatedAction {
+    let distribution = WeightedIndex::new(translated_actions.iter().map(|translated_action| translated_action.probability)).unwrap();
+    return &translated_actions[distribution.sample(rng)]
+}
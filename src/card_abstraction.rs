@@ -0,0 +1,516 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use hand_isomorphism_rust::deck::Card;
+use itertools::Itertools;
+use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{BOARD_CARD_AMOUNTS, COMMUNITY_CARD_AMOUNT, PRIVATE_CARD_AMOUNT, ROUNDS};
+use crate::game_states::nlth_poker::game_state::NLTHGameState;
+use crate::game_states::nlth_poker::hand_index::get_hand_index;
+use crate::game_states::nlth_poker::rank::{get_combo_index, get_hole_card_combo, rank_hole_card_combos, BLOCKED_HAND_RANK};
+
+/*
+    The card abstraction groups hands of a round that play alike into buckets, so a solver can use the bucket id instead of the cards.
+    Hands are compared by features of their hand strength, the chance to beat a random hand on the river (ties count half):
+        ExpectedHandStrength: the hand strength averaged over the runouts of the board (EHS)
+        ExpectedHandStrengthSquared: the average of the squared hand strength (EHS²), which favours hands that can improve a lot
+        HandStrengthDistribution: how the hand strength on the river is spread over the runouts in a number of equally wide bins (distribution-aware)
+        PotentialAware: how the hand is spread over the buckets of the next round, over the cards that can come next (potential-aware).
+                        Unlike a distribution over the river, it tells apart a hand that improves on the turn from one that needs the river.
+                        It needs the bucket table of the next round to hold every hand that can follow, so tables are built from the river back.
+    Buckets are made with k-means, using the euclidean distance for EHS and EHS² and the earth mover's distance for the others.
+    Bucket 0 holds the weakest hands. Hands are keyed by their hand index, so suit isomorphic hands always share a bucket.
+
+    Every bucket has a strength, the average of its hands: their EHS, EHS², mean of the distribution, or expected strength of their next round bucket.
+    The earth mover's distance of potential-aware features takes the difference in strength as the distance between two buckets of the next round.
+    That puts the next round buckets on a line, so the distance stays exact and fast, where the distance between the cluster centers would need
+    a transport problem to be solved for every pair of hands.
+
+    When a hand has no more than `samples` runouts (or ways to deal the next round) they are all enumerated, otherwise `samples` random ones are used.
+    The river has 1 runout, the turn 46, the flop 1081 and the preflop 2,118,760, so the preflop needs sampling.
+    Every runout ranks all 1326 hole card combos, so a complete flop table (1,286,792 canonical hands) of the hand strength features
+    still takes hours even with few samples. Potential-aware features only look up buckets, but need the table of the next round to be complete.
+
+    Bucket tables are saved as bytes:
+        "CFRB", version (1 byte), round (1 byte), feature (1 byte, 0 = EHS, 1 = EHS², 2 = distribution, 3 = potential-aware) and its bins (u32),
+        bucket amount (u32), the amount of buckets that hold hands (u32) and the strength of each (f32),
+        hand amount (u64) and per hand the hand index (u64) and bucket (u32), all little-endian
+*/
+
+const BUCKET_TABLE_MAGIC: &[u8; 4] = b"CFRB";
+const BUCKET_TABLE_VERSION: u8 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandFeature {
+    ExpectedHandStrength,
+    ExpectedHandStrengthSquared,
+    // The amount of bins
+    HandStrengthDistribution(usize),
+    // The amount of buckets in the next round
+    PotentialAware(usize),
+}
+
+impl HandFeature {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            HandFeature::HandStrengthDistribution(_) => return earth_movers_distance(a, b),
+            // The points are already cumulative, see to_cumulative_gaps
+            HandFeature::PotentialAware(_) => return a.iter().zip(b).map(|(x, y)| f32::abs(x - y)).sum(),
+            _ => return a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt(),
+        }
+    }
+
+    fn check_bins(&self) -> Result<(), String> {
+        match self {
+            HandFeature::HandStrengthDistribution(0) | HandFeature::PotentialAware(0) => return Err(format!("{:?} needs at least one bin", self)),
+            _ => return Ok(()),
+        }
+    }
+}
+
+// The chance to beat a random hand that doesn't share cards, on a complete board
+pub fn hand_strength(hole_cards: [Card; PRIVATE_CARD_AMOUNT], board: &[Card; COMMUNITY_CARD_AMOUNT]) -> f32 {
+    let hand_ranks = rank_hole_card_combos(board);
+    let hand_rank = hand_ranks[get_combo_index(hole_cards[0], hole_cards[1])];
+    let (mut wins, mut total) = (0.0, 0.0);
+    for (combo_index, &opponent_hand_rank) in hand_ranks.iter().enumerate() {
+        let opponent_hole_cards = get_hole_card_combo(combo_index);
+        if opponent_hand_rank == BLOCKED_HAND_RANK || opponent_hole_cards.iter().any(|card| hole_cards.contains(card)) {
+            continue
+        }
+        if hand_rank > opponent_hand_rank {
+            wins += 1.0;
+        } else if hand_rank == opponent_hand_rank {
+            wins += 0.5;
+        }
+        total += 1.0;
+    }
+    return wins / total
+}
+
+// Every way to draw `card_amount` cards from the deck, or `samples` random draws when there are more, passed to `deal`
+fn deal_cards<T>(deck: &[Card], card_amount: usize, samples: u64, rng: &mut StdRng, mut deal: impl FnMut(&[Card]) -> T) -> Vec<T> {
+    let deal_amount = (0..card_amount as u64).fold(1u64, |total, i| total * (deck.len() as u64 - i) / (i + 1));
+    if deal_amount <= samples {
+        return deck.iter().copied().combinations(card_amount).map(|cards| deal(&cards)).collect()
+    }
+    return (0..samples).map(|_| {
+        let cards = deck.choose_multiple(rng, card_amount).copied().collect::<Vec<Card>>();
+        return deal(&cards)
+    }).collect()
+}
+
+fn get_rng(rng_seed: Option<u64>) -> StdRng {
+    if let Some(seed) = rng_seed {
+        return StdRng::seed_from_u64(seed)
+    }
+    return StdRng::seed_from_u64(thread_rng().next_u64())
+}
+
+fn check_board(hole_cards: [Card; PRIVATE_CARD_AMOUNT], board: &[Card]) -> Result<(), String> {
+    if board.len() > COMMUNITY_CARD_AMOUNT {
+        return Err(format!("A board has at most {} cards, got {}", COMMUNITY_CARD_AMOUNT, board.len()))
+    }
+    let cards = [&hole_cards[..], board].concat();
+    if cards.iter().any(|&card| card >= 52) || cards.iter().enumerate().any(|(i, card)| cards[..i].contains(card)) {
+        return Err("The hole cards and board need to be different valid cards".to_string())
+    }
+    return Ok(())
+}
+
+pub fn get_hand_features(hole_cards: [Card; PRIVATE_CARD_AMOUNT], board: &[Card], feature: HandFeature, samples: u64, rng_seed: Option<u64>) -> Result<Vec<f32>, String> {
+    if samples == 0 {
+        return Err("Hand features need at least one sample".to_string())
+    }
+    check_board(hole_cards, board)?;
+    feature.check_bins()?;
+
+    let deck = (0..52).filter(|card| !hole_cards.contains(card) && !board.contains(card)).collect::<Vec<Card>>();
+    let mut full_board = [0; COMMUNITY_CARD_AMOUNT];
+    full_board[..board.len()].copy_from_slice(board);
+    let hand_strengths = deal_cards(&deck, COMMUNITY_CARD_AMOUNT - board.len(), samples, &mut get_rng(rng_seed), |runout| {
+        full_board[board.len()..].copy_from_slice(runout);
+        return hand_strength(hole_cards, &full_board)
+    });
+    let runout_amount = hand_strengths.len() as f32;
+    match feature {
+        HandFeature::ExpectedHandStrength => return Ok(vec![hand_strengths.iter().sum::<f32>() / runout_amount]),
+        HandFeature::ExpectedHandStrengthSquared => return Ok(vec![hand_strengths.iter().map(|hand_strength| hand_strength * hand_strength).sum::<f32>() / runout_amount]),
+        HandFeature::HandStrengthDistribution(bins) => {
+            let mut distribution = vec![0.0; bins];
+            for hand_strength in hand_strengths {
+                distribution[((hand_strength * bins as f32) as usize).min(bins - 1)] += 1.0 / runout_amount;
+            }
+            return Ok(distribution)
+        },
+        HandFeature::PotentialAware(_) => return Err("Potential-aware features need the bucket table of the next round, see get_potential_aware_features".to_string()),
+    }
+}
+
+/*
+    The share of the ways to deal the next round that put the hand in each bucket of `next_round_table`,
+    on every way or on `samples` random ones when there are more.
+*/
+pub fn get_potential_aware_features(
+    hole_cards: [Card; PRIVATE_CARD_AMOUNT],
+    board: &[Card],
+    next_round_table: &BucketTable,
+    samples: u64,
+    rng_seed: Option<u64>,
+) -> Result<Vec<f32>, String> {
+    if samples == 0 {
+        return Err("Hand features need at least one sample".to_string())
+    }
+    check_board(hole_cards, board)?;
+    let next_round = next_round_table.round;
+    if next_round == 0 || board.len() != BOARD_CARD_AMOUNTS[next_round - 1] {
+        return Err(format!("The bucket table of round {} doesn't follow a board of {} cards", next_round, board.len()))
+    }
+
+    let deck = (0..52).filter(|card| !hole_cards.contains(card) && !board.contains(card)).collect::<Vec<Card>>();
+    let mut next_board = [board, &[0; COMMUNITY_CARD_AMOUNT][..BOARD_CARD_AMOUNTS[next_round] - board.len()]].concat();
+    let next_buckets = deal_cards(&deck, BOARD_CARD_AMOUNTS[next_round] - board.len(), samples, &mut get_rng(rng_seed), |next_cards| {
+        next_board[board.len()..].copy_from_slice(next_cards);
+        return next_round_table.get_bucket(hole_cards, &next_board)
+            .ok_or(format!("The bucket table of round {} doesn't hold every hand that can follow", next_round))
+    }).into_iter().collect::<Result<Vec<u32>, String>>()?;
+
+    let mut histogram = vec![0.0; next_round_table.bucket_strengths.len()];
+    for bucket in next_buckets.iter() {
+        histogram[*bucket as usize] += 1.0 / next_buckets.len() as f32;
+    }
+    return Ok(histogram)
+}
+
+// The cumulative shares scaled by the gap to the strength of the next bucket, the L1 distance between two of them is their earth mover's distance
+fn to_cumulative_gaps(histogram: &[f32], bucket_strengths: &[f32]) -> Vec<f32> {
+    let mut cumulative_share = 0.0;
+    return histogram.iter().enumerate().map(|(bucket, share)| {
+        cumulative_share += share;
+        let gap = bucket_strengths.get(bucket + 1).map_or(0.0, |next_strength| next_strength - bucket_strengths[bucket]);
+        return cumulative_share * gap
+    }).collect()
+}
+
+// For distributions over the same bins, the earth mover's distance is the area between their cumulative distributions
+pub fn earth_movers_distance(a: &[f32], b: &[f32]) -> f32 {
+    let mut distance = 0.0;
+    let mut carried = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        carried += x - y;
+        distance += f32::abs(carried);
+    }
+    return distance
+}
+
+// The hand index of the hole cards and the board of a round, in the order the cards were dealt
+fn get_hand_key(round: usize, hole_cards: [Card; PRIVATE_CARD_AMOUNT], board: &[Card]) -> Result<u64, String> {
+    return get_hand_index(round, &[&hole_cards[..], board].concat())
+}
+
+/*
+    Clusters the points into at most `cluster_amount` clusters and returns the cluster of every point.
+    The first centers are picked with k-means++, after which points move to the closest center and every center to the mean of its points,
+    until no point moves or after `iterations` rounds.
+*/
+pub fn k_means(points: &[Vec<f32>], cluster_amount: usize, iterations: usize, feature: HandFeature, rng_seed: Option<u64>) -> Vec<usize> {
+    let mut rng = get_rng(rng_seed);
+    let cluster_amount = cluster_amount.min(points.len());
+    if cluster_amount == 0 {
+        return Vec::new()
+    }
+
+    let mut centers = vec![points[rng.gen_range(0..points.len())].clone()];
+    while centers.len() < cluster_amount {
+        let distances = points.iter()
+            .map(|point| centers.iter().map(|center| feature.distance(point, center)).fold(f32::MAX, f32::min))
+            .map(|distance| distance * distance)
+            .collect::<Vec<f32>>();
+        // Once every point is on a center, the remaining centers are picked at random
+        let point_index = match WeightedIndex::new(&distances) {
+            Ok(distribution) => distribution.sample(&mut rng),
+            Err(_) => rng.gen_range(0..points.len()),
+        };
+        centers.push(points[point_index].clone());
+    }
+
+    let closest_center = |centers: &[Vec<f32>], point: &[f32]| {
+        return (0..centers.len())
+            .min_by(|&a, &b| feature.distance(point, &centers[a]).total_cmp(&feature.distance(point, &centers[b])))
+            .unwrap()
+    };
+    let mut clusters = points.iter().map(|point| closest_center(&centers, point)).collect::<Vec<usize>>();
+    for _ in 0..iterations {
+        for (cluster, center) in centers.iter_mut().enumerate() {
+            let cluster_points = points.iter().zip(clusters.iter()).filter(|(_, &point_cluster)| point_cluster == cluster).map(|(point, _)| point).collect::<Vec<_>>();
+            // A cluster that lost all its points keeps its center
+            if cluster_points.is_empty() {
+                continue
+            }
+            for (dimension, value) in center.iter_mut().enumerate() {
+                *value = cluster_points.iter().map(|point| point[dimension]).sum::<f32>() / cluster_points.len() as f32;
+            }
+        }
+        let new_clusters = points.iter().map(|point| closest_center(&centers, point)).collect::<Vec<usize>>();
+        if new_clusters == clusters {
+            break
+        }
+        clusters = new_clusters;
+    }
+    return clusters
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BucketTable {
+    pub round: usize,
+    pub feature: HandFeature,
+    pub bucket_amount: u32,
+    // The average strength of the hands in every bucket, increasing with the bucket
+    pub bucket_strengths: Vec<f32>,
+    // Hand index to bucket
+    buckets: HashMap<u64, u32>,
+}
+
+// The hand index, hole cards and board of a hand
+type CanonicalHand<'a> = (u64, [Card; PRIVATE_CARD_AMOUNT], &'a [Card]);
+
+// One hand of every suit isomorphic group in the hands of a round, sorted by their hand index
+fn get_canonical_hands(round: usize, hands: &[([Card; PRIVATE_CARD_AMOUNT], Vec<Card>)]) -> Result<Vec<CanonicalHand<'_>>, String> {
+    if round >= ROUNDS {
+        return Err(format!("Invalid round {}", round))
+    }
+    let mut canonical_hands = HashMap::new();
+    for (hole_cards, board) in hands.iter() {
+        if board.len() != BOARD_CARD_AMOUNTS[round] {
+            return Err(format!("Round {} has {} board cards, got {}", round, BOARD_CARD_AMOUNTS[round], board.len()))
+        }
+        canonical_hands.entry(get_hand_key(round, *hole_cards, board)?).or_insert((*hole_cards, &board[..]));
+    }
+    return Ok(canonical_hands.into_iter().sorted_by_key(|&(key, _)| key).map(|(key, (hole_cards, board))| (key, hole_cards, board)).collect())
+}
+
+impl BucketTable {
+    /*
+        Buckets the given hands of a round, the board of every hand needs the amount of cards of that round.
+        Suit isomorphic hands are only computed once, on no more than `samples` runouts each.
+    */
+    pub fn build(
+        round: usize,
+        hands: &[([Card; PRIVATE_CARD_AMOUNT], Vec<Card>)],
+        feature: HandFeature,
+        bucket_amount: u32,
+        iterations: usize,
+        samples: u64,
+        rng_seed: Option<u64>,
+    ) -> Result<Self, String> {
+        if let HandFeature::PotentialAware(_) = feature {
+            return Err("Potential-aware tables are built from the table of the next round, see build_potential_aware".to_string())
+        }
+        feature.check_bins()?;
+        let canonical_hands = get_canonical_hands(round, hands)?;
+
+        let mut rng = get_rng(rng_seed);
+        let features = canonical_hands.iter()
+            .map(|&(_, hole_cards, board)| get_hand_features(hole_cards, board, feature, samples, Some(rng.next_u64())))
+            .collect::<Result<Vec<Vec<f32>>, String>>()?;
+        let hands = canonical_hands.iter().zip(features).map(|(&(key, _, _), features)| {
+            let strength = match feature {
+                HandFeature::HandStrengthDistribution(bins) => features.iter().enumerate().map(|(bin, share)| share * (bin as f32 + 0.5) / bins as f32).sum(),
+                _ => features[0],
+            };
+            return (key, features, strength)
+        }).collect::<Vec<_>>();
+        return BucketTable::from_features(round, feature, bucket_amount, &hands, iterations, rng_seed)
+    }
+
+    /*
+        Buckets the given hands of a round by how they are spread over the buckets of `next_round_table`, which needs to hold
+        every hand that can follow them. Suit isomorphic hands are only computed once, on no more than `samples` ways to deal the next round each.
+    */
+    pub fn build_potential_aware(
+        round: usize,
+        hands: &[([Card; PRIVATE_CARD_AMOUNT], Vec<Card>)],
+        next_round_table: &BucketTable,
+        bucket_amount: u32,
+        iterations: usize,
+        samples: u64,
+        rng_seed: Option<u64>,
+    ) -> Result<Self, String> {
+        if next_round_table.round != round + 1 {
+            return Err(format!("The table of round {} doesn't follow round {}", next_round_table.round, round))
+        }
+        let canonical_hands = get_canonical_hands(round, hands)?;
+
+        let mut rng = get_rng(rng_seed);
+        let histograms = canonical_hands.iter()
+            .map(|&(_, hole_cards, board)| get_potential_aware_features(hole_cards, board, next_round_table, samples, Some(rng.next_u64())))
+            .collect::<Result<Vec<Vec<f32>>, String>>()?;
+        let next_round_strengths = &next_round_table.bucket_strengths;
+        let hands = canonical_hands.iter().zip(histograms).map(|(&(key, _, _), histogram)| {
+            // The expected strength of the bucket the hand ends up in
+            let strength = histogram.iter().zip(next_round_strengths).map(|(share, strength)| share * strength).sum();
+            return (key, to_cumulative_gaps(&histogram, next_round_strengths), strength)
+        }).collect::<Vec<_>>();
+        let feature = HandFeature::PotentialAware(next_round_strengths.len());
+        feature.check_bins()?;
+        return BucketTable::from_features(round, feature, bucket_amount, &hands, iterations, rng_seed)
+    }
+
+    // Clusters the features of the hands into buckets, numbered by the strength of their hands
+    fn from_features(
+        round: usize,
+        feature: HandFeature,
+        bucket_amount: u32,
+        hands: &[(u64, Vec<f32>, f32)],
+        iterations: usize,
+        rng_seed: Option<u64>,
+    ) -> Result<Self, String> {
+        if bucket_amount == 0 {
+            return Err("A bucket table needs at least one bucket".to_string())
+        }
+        let features = hands.iter().map(|(_, features, _)| features.clone()).collect::<Vec<Vec<f32>>>();
+        let clusters = k_means(&features, bucket_amount as usize, iterations, feature, rng_seed);
+
+        // Number the buckets from weak to strong
+        let cluster_amount = clusters.iter().copied().max().map_or(0, |cluster| cluster + 1);
+        let cluster_strengths = (0..cluster_amount).map(|cluster| {
+            let cluster_hand_strengths = clusters.iter().zip(hands).filter(|(&point_cluster, _)| point_cluster == cluster).map(|(_, &(_, _, strength))| strength).collect::<Vec<f32>>();
+            return cluster_hand_strengths.iter().sum::<f32>() / cluster_hand_strengths.len().max(1) as f32
+        }).collect::<Vec<f32>>();
+        let mut bucket_of_cluster = vec![0; cluster_amount];
+        let mut bucket_strengths = vec![0.0; cluster_amount];
+        for (bucket, cluster) in (0..cluster_amount).sorted_by(|&a, &b| cluster_strengths[a].total_cmp(&cluster_strengths[b])).enumerate() {
+            bucket_of_cluster[cluster] = bucket as u32;
+            bucket_strengths[bucket] = cluster_strengths[cluster];
+        }
+
+        let buckets = hands.iter().zip(clusters).map(|(&(key, _, _), cluster)| (key, bucket_of_cluster[cluster])).collect();
+        return Ok(BucketTable { round, feature, bucket_amount, bucket_strengths, buckets })
+    }
+
+    pub fn get_bucket(&self, hole_cards: [Card; PRIVATE_CARD_AMOUNT], board: &[Card]) -> Option<u32> {
+        return self.buckets.get(&get_hand_key(self.round, hole_cards, board).ok()?).copied()
+    }
+
+    // The amount of suit isomorphic hands in the table
+    pub fn len(&self) -> usize {
+        return self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.buckets.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BUCKET_TABLE_MAGIC.to_vec();
+        bytes.push(BUCKET_TABLE_VERSION);
+        bytes.push(self.round as u8);
+        let (feature_tag, bins) = match self.feature {
+            HandFeature::ExpectedHandStrength => (0, 0),
+            HandFeature::ExpectedHandStrengthSquared => (1, 0),
+            HandFeature::HandStrengthDistribution(bins) => (2, bins as u32),
+            HandFeature::PotentialAware(bins) => (3, bins as u32),
+        };
+        bytes.push(feature_tag);
+        bytes.extend(bins.to_le_bytes());
+        bytes.extend(self.bucket_amount.to_le_bytes());
+        bytes.extend((self.bucket_strengths.len() as u32).to_le_bytes());
+        for strength in self.bucket_strengths.iter() {
+            bytes.extend(strength.to_le_bytes());
+        }
+        bytes.extend((self.buckets.len() as u64).to_le_bytes());
+        // Sorted so the same table always gives the same bytes
+        for (key, bucket) in self.buckets.iter().sorted() {
+            bytes.extend(key.to_le_bytes());
+            bytes.extend(bucket.to_le_bytes());
+        }
+        return bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut position = 0;
+        let mut take = |amount: usize| {
+            let taken = bytes.get(position..position + amount).ok_or("bucket table ended early".to_string())?;
+            position += amount;
+            return Ok::<&[u8], String>(taken)
+        };
+        if take(4)? != BUCKET_TABLE_MAGIC {
+            return Err("not a bucket table".to_string())
+        }
+        let version = take(1)?[0];
+        if version != BUCKET_TABLE_VERSION {
+            return Err(format!("unsupported bucket table version {}", version))
+        }
+        let round = take(1)?[0] as usize;
+        let feature_tag = take(1)?[0];
+        let bins = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+        let feature = match feature_tag {
+            0 => HandFeature::ExpectedHandStrength,
+            1 => HandFeature::ExpectedHandStrengthSquared,
+            2 => HandFeature::HandStrengthDistribution(bins),
+            3 => HandFeature::PotentialAware(bins),
+            _ => return Err(format!("invalid hand feature {}", feature_tag)),
+        };
+        let bucket_amount = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        let filled_bucket_amount = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        let bucket_strengths = (0..filled_bucket_amount)
+            .map(|_| Ok(f32::from_le_bytes(take(4)?.try_into().unwrap())))
+            .collect::<Result<Vec<f32>, String>>()?;
+        let hand_amount = u64::from_le_bytes(take(8)?.try_into().unwrap());
+        let mut buckets = HashMap::new();
+        for _ in 0..hand_amount {
+            let key = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let bucket = u32::from_le_bytes(take(4)?.try_into().unwrap());
+            buckets.insert(key, bucket);
+        }
+        if round >= ROUNDS {
+            return Err(format!("invalid round {}", round))
+        }
+        if buckets.values().any(|&bucket| bucket as usize >= bucket_strengths.len()) {
+            return Err("bucket table has a bucket without a strength".to_string())
+        }
+        return Ok(BucketTable { round, feature, bucket_amount, bucket_strengths, buckets })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        return fs::write(path, self.to_bytes()).map_err(|error| format!("could not write {}: {}", path.display(), error))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+        return BucketTable::from_bytes(&bytes)
+    }
+}
+
+// A bucket table per round, rounds without one are left unabstracted
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CardAbstraction {
+    tables: [Option<BucketTable>; ROUNDS],
+}
+
+impl CardAbstraction {
+    pub fn new(tables: Vec<BucketTable>) -> Result<Self, String> {
+        let mut card_abstraction = CardAbstraction::default();
+        for table in tables {
+            let round = table.round;
+            if card_abstraction.tables[round].replace(table).is_some() {
+                return Err(format!("round {} has more than one bucket table", round))
+            }
+        }
+        return Ok(card_abstraction)
+    }
+
+    pub fn get_table(&self, round: usize) -> Option<&BucketTable> {
+        return self.tables.get(round)?.as_ref()
+    }
+
+    // The bucket of what a player can see in the current round, to use in place of the cards in information set keys
+    pub fn get_bucket(&self, game_state: &NLTHGameState, player_index: usize) -> Option<u32> {
+        let board = &game_state.community_cards[..BOARD_CARD_AMOUNTS[game_state.round]];
+        return self.get_table(game_state.round)?.get_bucket(game_state.private_hands[player_index], board)
+    }
+}
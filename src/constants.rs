@@ -4,4 +4,7 @@ pub const MAX_PLAYERS: usize = 6;
 pub const ROUNDS: usize = 4;
 pub const PRIVATE_CARD_AMOUNT: usize = 2;
 pub const COMMUNITY_CARD_AMOUNT: usize = 5;
+// The community cards that are dealt once a round starts
+pub const BOARD_CARD_AMOUNTS: [usize; ROUNDS] = [0, 3, 4, 5];
+pub const HOLE_CARD_COMBOS: usize = 1326;
 pub const NO_CARD_PLACEHOLDER: Card = 52;
\ No newline at end of file
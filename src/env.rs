@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
+use crate::action_abstraction::ActionAbstraction;
+use crate::constants::MAX_PLAYERS;
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::observation::ObservationTensors;
+use crate::structs::{Action, ActionIdentifier};
+
+// Observation, rewards and whether the game is done
+pub type StepResult = (Vec<f32>, [i32; MAX_PLAYERS], bool);
+// Flattened observations, and the rewards and done flags per environment
+pub type VecStepResult = (Vec<f32>, Vec<[i32; MAX_PLAYERS]>, Vec<bool>);
+
+/*
+    Gym-style reinforcement learning environment around a game state.
+    Actions are chosen by ActionIdentifier of the action abstraction, the legal action mask has one entry per identifier.
+    The observation returned by `reset` and `step` is the information state tensor of the player that has to act next,
+    or of the player that acted last once the game is done. Rewards are the payoffs in chips, which are all 0 until the game is done.
+*/
+pub struct Env<T> {
+    pub player_amount: usize,
+    pub game_state: T,
+    pub action_abstraction: Arc<ActionAbstraction>,
+    last_player_index: usize,
+}
+
+impl<T: GameState + ObservationTensors> Env<T> {
+    pub fn new(player_amount: usize) -> Self {
+        return Env::with_action_abstraction(player_amount, Arc::new(ActionAbstraction::default()))
+    }
+
+    // The action abstraction is shared, so a VecEnv doesn't hold a copy per environment
+    pub fn with_action_abstraction(player_amount: usize, action_abstraction: Arc<ActionAbstraction>) -> Self {
+        let game_state = T::new_empty(player_amount, true, Some(0));
+        let last_player_index = game_state.get_active_player_index();
+        return Env { player_amount, game_state, action_abstraction, last_player_index }
+    }
+
+    pub fn action_amount(&self) -> usize {
+        return self.action_abstraction.action_amount()
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.game_state = T::new_empty(self.player_amount, true, Some(seed));
+        self.last_player_index = self.game_state.get_active_player_index();
+        return self.observation()
+    }
+
+    pub fn is_done(&self) -> bool {
+        return self.game_state.is_terminal()
+    }
+
+    // The player whose point of view is returned as observation
+    pub fn current_player_index(&self) -> usize {
+        if self.is_done() {
+            return self.last_player_index
+        }
+        return self.game_state.get_active_player_index()
+    }
+
+    pub fn observation(&self) -> Vec<f32> {
+        return self.game_state.information_state_tensor(self.current_player_index())
+    }
+
+    pub fn legal_actions(&self) -> SmallVec<[Action; 40]> {
+        if self.is_done() {
+            return SmallVec::new()
+        }
+        return self.action_abstraction.get_active_player_actions(&self.game_state)
+    }
+
+    pub fn legal_action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; self.action_amount()];
+        for action in self.legal_actions() {
+            if let Some(action_identifier) = self.action_abstraction.get_identifier(&action) {
+                mask[action_identifier as usize] = true;
+            }
+        }
+        return mask
+    }
+
+    pub fn check_action(&self, action_identifier: ActionIdentifier) -> Result<Action, String> {
+        if self.is_done() {
+            return Err("the game is done, call reset first".to_owned());
+        }
+        let action = self.action_abstraction.get_action(action_identifier)
+            .ok_or(format!("unknown ActionIdentifier {}", action_identifier))?;
+        if !self.legal_actions().contains(&action) {
+            return Err(format!("{} is not a legal action", action.as_string()));
+        }
+        return Ok(action)
+    }
+
+    pub fn step(&mut self, action_identifier: ActionIdentifier) -> Result<StepResult, String> {
+        let action = self.check_action(action_identifier)?;
+        self.last_player_index = self.game_state.get_active_player_index();
+        self.game_state = self.game_state.handle_action(action);
+
+        let done = self.is_done();
+        let rewards = if done { self.game_state.get_payoffs() } else { [0; MAX_PLAYERS] };
+        return Ok((self.observation(), rewards, done))
+    }
+}
+
+/*
+    Steps many independent environments in one call. Finished games are reset right away, so the returned observation
+    of a finished environment already belongs to its next game while the rewards and done flag are of the finished one.
+    Observations and masks are returned flattened, environment major.
+*/
+pub struct VecEnv<T> {
+    pub envs: Vec<Env<T>>,
+    next_seed: u64,
+}
+
+impl<T: GameState + ObservationTensors> VecEnv<T> {
+    pub fn new(player_amount: usize, env_amount: usize) -> Self {
+        return VecEnv::with_action_abstraction(player_amount, env_amount, Arc::new(ActionAbstraction::default()))
+    }
+
+    pub fn with_action_abstraction(player_amount: usize, env_amount: usize, action_abstraction: Arc<ActionAbstraction>) -> Self {
+        return VecEnv {
+            envs: (0..env_amount).map(|_| Env::with_action_abstraction(player_amount, action_abstraction.clone())).collect(),
+            next_seed: 0,
+        }
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.next_seed = seed;
+        let mut observations = Vec::with_capacity(self.envs.len() * T::tensor_shape().information_state);
+        for env_index in 0..self.envs.len() {
+            let seed = self.take_seed();
+            observations.extend(self.envs[env_index].reset(seed));
+        }
+        return observations
+    }
+
+    pub fn legal_action_masks(&self) -> Vec<bool> {
+        return self.envs.iter().flat_map(|env| env.legal_action_mask()).collect()
+    }
+
+    pub fn step(&mut self, action_identifiers: &[ActionIdentifier]) -> Result<VecStepResult, String> {
+        if action_identifiers.len() != self.envs.len() {
+            return Err(format!("expected {} actions, got {}", self.envs.len(), action_identifiers.len()));
+        }
+        // Check all actions first, so an illegal action doesn't leave the environments half stepped
+        for (env_index, &action_identifier) in action_identifiers.iter().enumerate() {
+            self.envs[env_index].check_action(action_identifier).map_err(|error| format!("environment {}: {}", env_index, error))?;
+        }
+
+        let mut observations = Vec::with_capacity(self.envs.len() * T::tensor_shape().information_state);
+        let mut rewards = Vec::with_capacity(self.envs.len());
+        let mut dones = Vec::with_capacity(self.envs.len());
+        for (env_index, &action_identifier) in action_identifiers.iter().enumerate() {
+            let (mut observation, env_rewards, done) = self.envs[env_index].step(action_identifier)?;
+            if done {
+                let seed = self.take_seed();
+                observation = self.envs[env_index].reset(seed);
+            }
+            observations.extend(observation);
+            rewards.push(env_rewards);
+            dones.push(done);
+        }
+        return Ok((observations, rewards, dones))
+    }
+
+    fn take_seed(&mut self) -> u64 {
+        let seed = self.next_seed;
+        self.next_seed += 1;
+        return seed
+    }
+}
@@ -0,0 +1,251 @@
+use hand_isomorphism_rust::deck::Card;
+use itertools::Itertools;
+use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+
+use crate::constants::{COMMUNITY_CARD_AMOUNT, HOLE_CARD_COMBOS, MAX_PLAYERS, PRIVATE_CARD_AMOUNT};
+use crate::game_states::nlth_poker::range_payoffs::RangeWeights;
+use crate::game_states::nlth_poker::rank::{get_hole_card_combo, rank_hand};
+
+/*
+    The chance of every player to win the pot when all cards are dealt, for 2 to 6 players.
+    Every player holds either known hole cards or a range, weights over the 1326 combos (indexed by get_combo_index).
+    Cards on the board and dead cards can't be dealt to anyone.
+        win: the chance to win the whole pot
+        tie: the chance to split the pot with one or more players
+        equity: the share of the pot the player gets on average, the win chance plus the share of every tie
+    When every way to deal the hands and the rest of the board fits in `samples` showdowns they are all enumerated and the result is exact,
+    otherwise `samples` random deals are played out. A deal where the hands of the ranges overlap is drawn again.
+*/
+
+#[derive(Clone, Copy, Debug)]
+pub enum HoleCards<'a> {
+    Hand([Card; PRIVATE_CARD_AMOUNT]),
+    Range(&'a RangeWeights),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EquityResult {
+    pub players: Vec<Equity>,
+    pub exact: bool,
+    // The amount of showdowns the result is based on
+    pub showdowns: u64,
+}
+
+// How often a Monte Carlo deal may be drawn again because the hands of the ranges overlap, before giving up
+const MAX_REDRAWS: u32 = 1000;
+
+pub fn calculate_equity(players: &[HoleCards], board: &[Card], dead_cards: &[Card], samples: u64, rng_seed: Option<u64>) -> Result<EquityResult, String> {
+    if players.len() < 2 || players.len() > MAX_PLAYERS {
+        return Err(format!("Equity needs 2 to {} players, got {}", MAX_PLAYERS, players.len()))
+    }
+    if board.len() > COMMUNITY_CARD_AMOUNT {
+        return Err(format!("A board has at most {} cards, got {}", COMMUNITY_CARD_AMOUNT, board.len()))
+    }
+    if samples == 0 {
+        return Err("Equity needs at least one sample".to_string())
+    }
+
+    // The cards nobody can be dealt, the hands that are known are only left out of the ranges
+    let mut dealt_cards = 0u64;
+    let mut known_cards = 0u64;
+    let known_hands = players.iter().filter_map(|hole_cards| match hole_cards {
+        HoleCards::Hand(hand) => Some(hand.as_slice()),
+        HoleCards::Range(_) => None,
+    });
+    for (card_index, &card) in board.iter().chain(dead_cards).chain(known_hands.flatten()).enumerate() {
+        if card >= 52 {
+            return Err(format!("Invalid card {}", card))
+        }
+        if known_cards & (1 << card) != 0 {
+            return Err(format!("Card {} is used more than once", card))
+        }
+        if card_index < board.len() + dead_cards.len() {
+            dealt_cards |= 1 << card;
+        }
+        known_cards |= 1 << card;
+    }
+
+    let combos = players.iter().map(|&hole_cards| get_combos(hole_cards, known_cards)).collect::<Vec<_>>();
+    if let Some(player_index) = combos.iter().position(|player_combos| player_combos.is_empty()) {
+        return Err(format!("The range of player {} has no combos left after removing the known cards", player_index))
+    }
+
+    // Every range still needs two cards and the board needs the rest of its cards
+    let missing_cards = COMMUNITY_CARD_AMOUNT - board.len();
+    let needed_cards = known_cards.count_ones() as u64 + 2 * players.iter().filter(|hole_cards| matches!(hole_cards, HoleCards::Range(_))).count() as u64 + missing_cards as u64;
+    if needed_cards > 52 {
+        return Err(format!("Dealing every hand and the rest of the board takes {} cards, the deck only has 52", needed_cards))
+    }
+    let remaining_cards = 52 - (needed_cards - missing_cards as u64);
+    let exact_showdowns = combos.iter()
+        .map(|player_combos| player_combos.len() as u64)
+        .chain(std::iter::once(get_combination_amount(remaining_cards, missing_cards as u64)))
+        .try_fold(1u64, |total, amount| total.checked_mul(amount));
+
+    let mut equity_counter = EquityCounter::new(players.len());
+    let exact = matches!(exact_showdowns, Some(showdowns) if showdowns <= samples);
+    if exact {
+        let mut hands = Vec::with_capacity(players.len());
+        enumerate_hands(&combos, board, dealt_cards, 1.0, &mut hands, &mut equity_counter);
+        if equity_counter.showdowns == 0 {
+            return Err("The hands of the ranges always overlap".to_string())
+        }
+    } else {
+        let mut rng = if let Some(seed) = rng_seed {
+            StdRng::seed_from_u64(seed)
+        } else {
+            StdRng::seed_from_u64(thread_rng().next_u64())
+        };
+        let distributions = combos.iter()
+            .map(|player_combos| WeightedIndex::new(player_combos.iter().map(|&(_, weight)| weight)).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut hands = Vec::with_capacity(players.len());
+        let mut full_board = [0; COMMUNITY_CARD_AMOUNT];
+        full_board[..board.len()].copy_from_slice(board);
+        for _ in 0..samples {
+            let used_cards = draw_hands(&combos, &distributions, dealt_cards, &mut hands, &mut rng)?;
+            let deck = (0..52u8).filter(|&card| used_cards & (1 << card) == 0).collect::<Vec<Card>>();
+            for (board_card, &card) in full_board[board.len()..].iter_mut().zip(deck.choose_multiple(&mut rng, missing_cards)) {
+                *board_card = card;
+            }
+            equity_counter.add_showdown(&hands, &full_board, 1.0);
+        }
+    }
+
+    return Ok(equity_counter.into_result(exact))
+}
+
+// The combos a player can hold with a weight above zero, together with that weight
+fn get_combos(hole_cards: HoleCards, known_cards: u64) -> Vec<([Card; PRIVATE_CARD_AMOUNT], f64)> {
+    match hole_cards {
+        HoleCards::Hand(hand) => return vec![(hand, 1.0)],
+        HoleCards::Range(range) => return (0..HOLE_CARD_COMBOS)
+            .filter(|&combo_index| range[combo_index] > 0.0)
+            .map(|combo_index| (get_hole_card_combo(combo_index), range[combo_index] as f64))
+            .filter(|(hand, _)| hand.iter().all(|&card| known_cards & (1 << card) == 0))
+            .collect(),
+    }
+}
+
+fn get_combination_amount(n: u64, k: u64) -> u64 {
+    return (0..k).fold(1, |total, i| total * (n - i) / (i + 1))
+}
+
+// Deals every combination of hands that don't overlap, followed by every way to complete the board
+fn enumerate_hands(
+    combos: &[Vec<([Card; PRIVATE_CARD_AMOUNT], f64)>],
+    board: &[Card],
+    used_cards: u64,
+    weight: f64,
+    hands: &mut Vec<[Card; PRIVATE_CARD_AMOUNT]>,
+    equity_counter: &mut EquityCounter,
+) {
+    let player_index = hands.len();
+    if player_index == combos.len() {
+        let mut full_board = [0; COMMUNITY_CARD_AMOUNT];
+        full_board[..board.len()].copy_from_slice(board);
+        let deck = (0..52u8).filter(|&card| used_cards & (1 << card) == 0).collect::<Vec<Card>>();
+        for missing_cards in deck.into_iter().combinations(COMMUNITY_CARD_AMOUNT - board.len()) {
+            full_board[board.len()..].copy_from_slice(&missing_cards);
+            equity_counter.add_showdown(hands, &full_board, weight);
+        }
+        return
+    }
+
+    for &(hand, hand_weight) in combos[player_index].iter() {
+        let hand_cards = (1u64 << hand[0]) | (1u64 << hand[1]);
+        if used_cards & hand_cards != 0 {
+            continue
+        }
+        hands.push(hand);
+        enumerate_hands(combos, board, used_cards | hand_cards, weight * hand_weight, hands, equity_counter);
+        hands.pop();
+    }
+}
+
+// Draws a hand for every player until none of them overlap, returns every card that is used
+fn draw_hands(
+    combos: &[Vec<([Card; PRIVATE_CARD_AMOUNT], f64)>],
+    distributions: &[WeightedIndex<f64>],
+    dealt_cards: u64,
+    hands: &mut Vec<[Card; PRIVATE_CARD_AMOUNT]>,
+    rng: &mut StdRng,
+) -> Result<u64, String> {
+    'redraw: for _ in 0..MAX_REDRAWS {
+        hands.clear();
+        let mut used_cards = dealt_cards;
+        for (player_combos, distribution) in combos.iter().zip(distributions) {
+            let hand = player_combos[distribution.sample(rng)].0;
+            let hand_cards = (1u64 << hand[0]) | (1u64 << hand[1]);
+            if used_cards & hand_cards != 0 {
+                continue 'redraw
+            }
+            used_cards |= hand_cards;
+            hands.push(hand);
+        }
+        return Ok(used_cards)
+    }
+    return Err(format!("Couldn't deal hands that don't overlap in {} tries", MAX_REDRAWS))
+}
+
+struct EquityCounter {
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    equities: Vec<f64>,
+    total_weight: f64,
+    showdowns: u64,
+}
+
+impl EquityCounter {
+    fn new(player_amount: usize) -> Self {
+        return EquityCounter {
+            wins: vec![0.0; player_amount],
+            ties: vec![0.0; player_amount],
+            equities: vec![0.0; player_amount],
+            total_weight: 0.0,
+            showdowns: 0,
+        }
+    }
+
+    fn add_showdown(&mut self, hands: &[[Card; PRIVATE_CARD_AMOUNT]], board: &[Card; COMMUNITY_CARD_AMOUNT], weight: f64) {
+        let mut hand_ranks = [0u16; MAX_PLAYERS];
+        for (hand_rank, hand) in hand_ranks.iter_mut().zip(hands) {
+            let mut cards = [0; PRIVATE_CARD_AMOUNT + COMMUNITY_CARD_AMOUNT];
+            cards[..PRIVATE_CARD_AMOUNT].copy_from_slice(hand);
+            cards[PRIVATE_CARD_AMOUNT..].copy_from_slice(board);
+            *hand_rank = rank_hand(&cards);
+        }
+        let hand_ranks = &hand_ranks[..hands.len()];
+        let highest_hand_rank = *hand_ranks.iter().max().unwrap();
+        let winner_amount = hand_ranks.iter().filter(|&&hand_rank| hand_rank == highest_hand_rank).count();
+
+        for player_index in (0..hands.len()).filter(|&player_index| hand_ranks[player_index] == highest_hand_rank) {
+            if winner_amount == 1 {
+                self.wins[player_index] += weight;
+            } else {
+                self.ties[player_index] += weight;
+            }
+            self.equities[player_index] += weight / winner_amount as f64;
+        }
+        self.total_weight += weight;
+        self.showdowns += 1;
+    }
+
+    fn into_result(self, exact: bool) -> EquityResult {
+        let players = (0..self.wins.len()).map(|player_index| Equity {
+            win: self.wins[player_index] / self.total_weight,
+            tie: self.ties[player_index] / self.total_weight,
+            equity: self.equities[player_index] / self.total_weight,
+        }).collect();
+        return EquityResult { players, exact, showdowns: self.showdowns }
+    }
+}
@@ -21,4 +21,16 @@ pub trait GameState {
     fn get_active_player_actions(&self, bets_in_abstraction_option: Option<&SmallVec<[Action; 40]>>) -> SmallVec<[Action; 40]>;
     fn handle_action(&self, action: Action) -> Self;
     fn can_proceed_to_next_round(&self) -> bool;
+
+    // The chips a bet or raise of the active player moves into the pot, and the stack they are taken from. None when bets have a fixed size
+    fn get_bet_commitment(&self, _action: &Action) -> Option<(u32, u32)> {
+        return None
+    }
+}
+
+// With the zero-sum-assertions feature enabled, panics when the payoffs of a terminal state don't add up to zero. Every variant returns its payoffs through this
+pub fn check_zero_sum(payoffs: [i32; MAX_PLAYERS]) -> [i32; MAX_PLAYERS] {
+    #[cfg(feature = "zero-sum-assertions")]
+    assert_eq!(payoffs.iter().sum::<i32>(), 0, "the payoffs {:?} do not add up to zero", payoffs);
+    return payoffs
 }
@@ -0,0 +1,305 @@
+use hand_isomorphism_rust::deck::Card;
+
+use crate::constants::{MAX_PLAYERS, NO_CARD_PLACEHOLDER, ROUNDS};
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::kuhn_poker::game_state::KPGameState;
+use crate::game_states::leduc_poker::game_state::LPGameState;
+use crate::game_states::nlth_poker::game_state::{NLTHGameState, OddChipRule};
+use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+use crate::structs::{Action, ActionIdentifier, BetRounding, RoundingMode, RoundingUnit};
+
+/*
+    Compact, canonical byte encoding of a game state, meant for hashing into transposition tables and storing large amounts of sampled states.
+    A state is fully determined by its setup, the cards and the actions taken, so that's all we store:
+        variant tag (1 byte)
+        NLTH only: player amount (1 byte), small blind, big blind, ante (LEB128 varints),
+            the bet rounding mode (1 byte) and unit (1 byte, 0 for the big blind or 1 followed by the chips as a varint),
+            the odd chip rule (1 byte, followed by the seat as a varint for FirstFromSeat) and the starting stack of every seat (varints)
+        cards (1 byte each, NO_CARD_PLACEHOLDER for unknown cards)
+        per round: the amount of actions (1 byte) followed by their ActionIdentifiers in the default action abstraction (1 byte each)
+    Decoding replays the actions with `handle_action`, so only actions that have an ActionIdentifier can be encoded,
+    and states whose chips don't follow their actions (translated bets) are rejected.
+*/
+
+const VARIANT_KUHN: u8 = 0;
+const VARIANT_LEDUC: u8 = 1;
+const VARIANT_NLTH: u8 = 2;
+
+pub trait StateEncoding: Sized {
+    fn encode(&self) -> Result<Vec<u8>, String>;
+    fn decode(bytes: &[u8]) -> Result<Self, String>;
+
+    // Stable 64-bit hash of the encoding (FNV-1a), so hashes can be stored on disk and compared between runs
+    fn state_hash(&self) -> Result<u64, String> {
+        return Ok(fnv1a_hash(&self.encode()?))
+    }
+}
+
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return hash
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn new(variant: u8) -> Self {
+        return Writer { bytes: vec![variant] }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn write_varint(&mut self, mut value: u32) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn write_cards(&mut self, cards: &[Card]) {
+        self.bytes.extend_from_slice(cards);
+    }
+
+    fn write_history(&mut self, history: &[Action]) -> Result<(), String> {
+        if history.len() > u8::MAX as usize {
+            return Err(format!("cannot encode a round with {} actions", history.len()));
+        }
+        self.write_u8(history.len() as u8);
+        for action in history {
+            let &action_identifier = action.into_identifier().ok_or(format!("{} has no ActionIdentifier", action.as_string()))?;
+            self.write_u8(action_identifier);
+        }
+        return Ok(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], variant: u8) -> Result<Self, String> {
+        if bytes.first() != Some(&variant) {
+            return Err("the encoding is of a different game".to_owned());
+        }
+        return Ok(Reader { bytes, position: 1 })
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        let value = *self.bytes.get(self.position).ok_or("unexpected end of the encoding")?;
+        self.position += 1;
+        return Ok(value)
+    }
+
+    fn read_varint(&mut self) -> Result<u32, String> {
+        let mut value: u32 = 0;
+        for shift in (0..32).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value)
+            }
+        }
+        return Err("varint is too long".to_owned())
+    }
+
+    fn read_card(&mut self) -> Result<Card, String> {
+        let card = self.read_u8()?;
+        // NO_CARD_PLACEHOLDER is the highest valid value
+        if card > NO_CARD_PLACEHOLDER {
+            return Err(format!("invalid card {}", card));
+        }
+        return Ok(card)
+    }
+
+    fn read_history(&mut self) -> Result<Vec<Action>, String> {
+        let action_amount = self.read_u8()?;
+        return (0..action_amount).map(|_| {
+            let action_identifier: ActionIdentifier = self.read_u8()?;
+            return DEFAULT_ACTION_ABSTRACTION.get_action(action_identifier)
+                .ok_or(format!("unknown ActionIdentifier {}", action_identifier))
+        }).collect()
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        if self.position != self.bytes.len() {
+            return Err("trailing bytes after the encoding".to_owned());
+        }
+        return Ok(())
+    }
+}
+
+// Replays the actions of all rounds and checks that they end up in the rounds they were encoded in
+fn replay<T: GameState>(mut game_state: T, histories: &[Vec<Action>]) -> Result<T, String> {
+    for (round, history) in histories.iter().enumerate() {
+        for &action in history {
+            if game_state.get_current_round_index() != round || game_state.is_terminal() {
+                return Err("the actions do not form a valid game".to_owned());
+            }
+            game_state = game_state.handle_action(action);
+        }
+    }
+    // Checks and raises are encoded as calls and bets
+    for (round, history) in histories.iter().enumerate() {
+        if !game_state.get_history()[round].iter().map(|action| action.without_check_raise()).eq(history.iter().copied()) {
+            return Err("the actions do not form a valid game".to_owned());
+        }
+    }
+    return Ok(game_state)
+}
+
+impl StateEncoding for NLTHGameState {
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut writer = Writer::new(VARIANT_NLTH);
+        writer.write_u8(self.player_amount as u8);
+        writer.write_varint(self.small_blind);
+        writer.write_varint(self.big_blind);
+        writer.write_varint(self.ante);
+        writer.write_u8(match self.bet_rounding.mode {
+            RoundingMode::Down => 0,
+            RoundingMode::Nearest => 1,
+            RoundingMode::Up => 2,
+        });
+        match self.bet_rounding.unit {
+            RoundingUnit::BigBlind => writer.write_u8(0),
+            RoundingUnit::Chips(chips) => {
+                writer.write_u8(1);
+                writer.write_varint(chips);
+            },
+        }
+        match self.odd_chip_rule {
+            OddChipRule::FirstLeftOfButton => writer.write_u8(0),
+            OddChipRule::FirstFromSeat(player_index) => {
+                writer.write_u8(1);
+                writer.write_varint(u32::try_from(player_index).map_err(|_| format!("cannot encode odd chip seat {}", player_index))?);
+            },
+        }
+        for (&stack, contributions) in self.stacks.iter().zip(self.get_contributions()) {
+            writer.write_varint(stack + contributions);
+        }
+        for private_hand in self.private_hands[..self.player_amount].iter() {
+            writer.write_cards(private_hand);
+        }
+        writer.write_cards(&self.community_cards);
+        for round in 0..ROUNDS {
+            writer.write_history(&self.history[round])?;
+        }
+
+        // A translated bet keeps the abstract action in the history while other chips went in, which the actions can't replay
+        let replayed_state = NLTHGameState::decode(&writer.bytes)?;
+        if replayed_state.bets != self.bets || replayed_state.stacks != self.stacks {
+            return Err("the actions do not replay to the chips that were bet".to_owned());
+        }
+        return Ok(writer.bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes, VARIANT_NLTH)?;
+        let player_amount = reader.read_u8()? as usize;
+        if !(2..=MAX_PLAYERS).contains(&player_amount) {
+            return Err(format!("invalid player amount {}", player_amount));
+        }
+        let small_blind = reader.read_varint()?;
+        let big_blind = reader.read_varint()?;
+        let ante = reader.read_varint()?;
+        let mode = match reader.read_u8()? {
+            0 => RoundingMode::Down,
+            1 => RoundingMode::Nearest,
+            2 => RoundingMode::Up,
+            mode => return Err(format!("invalid rounding mode {}", mode)),
+        };
+        let unit = match reader.read_u8()? {
+            0 => RoundingUnit::BigBlind,
+            1 => RoundingUnit::Chips(reader.read_varint()?),
+            unit => return Err(format!("invalid rounding unit {}", unit)),
+        };
+        let odd_chip_rule = match reader.read_u8()? {
+            0 => OddChipRule::FirstLeftOfButton,
+            1 => OddChipRule::FirstFromSeat(reader.read_varint()? as usize),
+            rule => return Err(format!("invalid odd chip rule {}", rule)),
+        };
+        let mut stacks = [0; MAX_PLAYERS];
+        for stack in stacks.iter_mut() {
+            *stack = reader.read_varint()?;
+        }
+        for (player_index, &stack) in stacks[..player_amount].iter().enumerate() {
+            let blind = if player_index == 0 { small_blind } else if player_index == 1 { big_blind } else { 0 };
+            if stack <= blind + ante {
+                return Err(format!("player {} cannot cover their blind and ante", player_index));
+            }
+        }
+
+        let mut game_state = NLTHGameState::new_with_table(player_amount, stacks, small_blind, big_blind, ante);
+        game_state.bet_rounding = BetRounding { mode, unit };
+        game_state.odd_chip_rule = odd_chip_rule;
+        for private_hand in game_state.private_hands[..player_amount].iter_mut() {
+            for card in private_hand.iter_mut() {
+                *card = reader.read_card()?;
+            }
+        }
+        for card in game_state.community_cards.iter_mut() {
+            *card = reader.read_card()?;
+        }
+        let histories = (0..ROUNDS).map(|_| reader.read_history()).collect::<Result<Vec<_>, String>>()?;
+        reader.finish()?;
+
+        let game_state = replay(game_state, &histories)?;
+        if game_state.stacks.iter().zip(game_state.get_contributions()).zip(stacks).any(|((&stack, contributions), starting_stack)| stack + contributions != starting_stack) {
+            return Err("the actions do not replay to the chips that were bet".to_owned());
+        }
+        return Ok(game_state)
+    }
+}
+
+impl StateEncoding for LPGameState {
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut writer = Writer::new(VARIANT_LEDUC);
+        writer.write_cards(&[self.private_hands[0][0], self.private_hands[1][0], self.community_cards[0]]);
+        for round in 0..LPGameState::get_total_rounds() {
+            writer.write_history(&self.history[round])?;
+        }
+        return Ok(writer.bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes, VARIANT_LEDUC)?;
+        let mut game_state = LPGameState::new_empty(2, false, None);
+        game_state.private_hands[0][0] = reader.read_card()?;
+        game_state.private_hands[1][0] = reader.read_card()?;
+        game_state.community_cards[0] = reader.read_card()?;
+        let histories = (0..LPGameState::get_total_rounds()).map(|_| reader.read_history()).collect::<Result<Vec<_>, String>>()?;
+        reader.finish()?;
+
+        return replay(game_state, &histories)
+    }
+}
+
+impl StateEncoding for KPGameState {
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut writer = Writer::new(VARIANT_KUHN);
+        writer.write_cards(&[self.private_hands[0][0], self.private_hands[1][0]]);
+        writer.write_history(&self.history[0])?;
+        return Ok(writer.bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader::new(bytes, VARIANT_KUHN)?;
+        let mut game_state = KPGameState::new_empty(2, false, None);
+        game_state.private_hands[0][0] = reader.read_card()?;
+        game_state.private_hands[1][0] = reader.read_card()?;
+        let histories = vec![reader.read_history()?];
+        reader.finish()?;
+
+        return replay(game_state, &histories)
+    }
+}
@@ -1,9 +1,12 @@
-use hand_isomorphism_rust::deck::{card_from_string, Card};
+use std::fmt;
+
+use hand_isomorphism_rust::deck::{card_from_string, deck_get_rank, Card};
 use rand::prelude::*;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 
-use crate::{constants::{COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT, ROUNDS}, game_states::base_game_state::GameState, structs::{Action, ActionType}};
+use crate::{constants::{COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT, ROUNDS}, game_states::{base_game_state::{check_zero_sum, GameState}, nlth_poker::{game_state::format_cards, rank::HandCategory}, observation::{push_one_hot, ObservationTensors, TensorShape}, showdown::{PotResult, Showdown, ShowdownResult, ShownHand}}, structs::{Action, ActionType, BetSizing}};
 
 lazy_static! {
     static ref DECK: [Card; 3] = {
@@ -15,7 +18,7 @@ lazy_static! {
     };
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KPGameState {
     pub player_amount: usize,
     pub private_hands: [[Card; PRIVATE_CARD_AMOUNT]; MAX_PLAYERS],
@@ -100,11 +103,11 @@ impl GameState for KPGameState {
 
     fn is_terminal(&self) -> bool {
         let terminal_histories: Vec<SmallVec<[Action; 200]>> = vec![
-            smallvec![Action { action_type: ActionType::Bet, raise_amount: 0 }, Action { action_type: ActionType::Fold, raise_amount: 0 }],
-            smallvec![Action { action_type: ActionType::Bet, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }],
-            smallvec![Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }],
-            smallvec![Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Bet, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }],
-            smallvec![Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Bet, raise_amount: 0 }, Action { action_type: ActionType::Fold, raise_amount: 0 }],
+            smallvec![Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }],
+            smallvec![Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }],
+            smallvec![Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }],
+            smallvec![Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }],
+            smallvec![Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }],
         ];
 
         if terminal_histories.contains(&self.history[0]) {
@@ -117,7 +120,7 @@ impl GameState for KPGameState {
     fn get_payoffs(&self) -> [i32; MAX_PLAYERS] {
         let winning_player_identifier: usize;
 
-        if let Some((i, _)) = self.history[0].iter().enumerate().find(|(_, &action)| action == Action { action_type: ActionType::Fold, raise_amount: 0 }) {
+        if let Some((i, _)) = self.history[0].iter().enumerate().find(|(_, &action)| action == Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }) {
             winning_player_identifier = (i+1) % 2;
         } else {
             // Showoff
@@ -137,7 +140,7 @@ impl GameState for KPGameState {
         }).collect::<Vec<i32>>().try_into().unwrap();
         payoffs[(winning_player_identifier + 1) % 2] *= -1;
 
-        return payoffs;
+        return check_zero_sum(payoffs);
     }
 
     fn get_active_player_index(&self) -> usize {
@@ -147,11 +150,11 @@ impl GameState for KPGameState {
     fn get_active_player_actions(&self, _actions_in_abstraction: Option<&SmallVec<[Action; 40]>>) -> SmallVec<[Action; 40]> {
         if let Some(&previous_action) = self.history[0].iter().rev().next() {
             if previous_action.action_type == ActionType::Bet {
-                return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }]
+                return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }]
             }
         }
 
-        return smallvec![Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Bet, raise_amount: 0 }]
+        return smallvec![Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }]
     }
 
     fn can_proceed_to_next_round(&self) -> bool {
@@ -160,6 +163,8 @@ impl GameState for KPGameState {
     }
 
     fn handle_action(&self, action: Action) -> Self {
+        // Kuhn poker stores checks and calls as passes (Call) and bets as Bet
+        let action = action.without_check_raise();
         let mut new_bets = self.bets.clone();
 
         let active_player_index = self.get_active_player_index();
@@ -190,3 +195,84 @@ impl GameState for KPGameState {
         return next_state
     }
 }
+
+impl fmt::Display for KPGameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Kuhn, pot {}{}", self.bets.iter().sum::<usize>(), if self.is_terminal() { " (terminal)" } else { "" })?;
+        writeln!(f, "  Player  Card  Bets")?;
+        for player_index in 0..self.player_amount {
+            writeln!(
+                f, "{} P{:<5}  {:<4}  {}",
+                if player_index == self.get_active_player_index() && !self.is_terminal() { ">" } else { " " },
+                player_index,
+                format_cards(&self.private_hands[player_index][..1]),
+                self.bets[player_index],
+            )?;
+        }
+        let actions = self.history[0].iter().map(|action| action.as_string()).collect::<Vec<String>>();
+        writeln!(f, "History: {}", actions.join(", "))?;
+
+        return Ok(())
+    }
+}
+
+// Rank of the jack, the lowest card in the deck
+const JACK_RANK: u8 = 9;
+const MAX_HISTORY_LENGTH: usize = 3;
+
+/*
+    Observation tensor (7 values):
+        [0, 2)   observing player, one-hot
+        [2, 5)   private card, one-hot in the order J, Q, K
+        [5, 7)   chips put in by each player, in units of the 100 chip ante
+    Information state tensor (13 values): the observation followed by
+        [7, 13)  up to 3 actions, each one-hot as (pass, bet). Checks, calls and folds are passes
+*/
+impl ObservationTensors for KPGameState {
+    fn tensor_shape() -> TensorShape {
+        return TensorShape { information_state: 7 + MAX_HISTORY_LENGTH * 2, observation: 7 }
+    }
+
+    fn observation_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = Vec::with_capacity(Self::tensor_shape().observation);
+        push_one_hot(&mut tensor, 2, Some(player_index));
+        push_one_hot(&mut tensor, 3, Some((deck_get_rank(self.private_hands[player_index][0]) - JACK_RANK) as usize));
+        tensor.extend(self.bets.iter().map(|&bets| bets as f32 / 100.0));
+        return tensor
+    }
+
+    fn information_state_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = self.observation_tensor(player_index);
+        for history_index in 0..MAX_HISTORY_LENGTH {
+            let action_index = self.history[0].get(history_index).map(|action| {
+                if action.action_type == ActionType::Bet {
+                    return 1
+                }
+                return 0
+            });
+            push_one_hot(&mut tensor, 2, action_index);
+        }
+        return tensor
+    }
+}
+
+impl Showdown for KPGameState {
+    fn get_showdown_result(&self) -> ShowdownResult {
+        let payoffs = self.get_payoffs();
+        let folded = self.history[0].iter().any(|action| action.action_type == ActionType::Fold);
+
+        // A single card is always a high card, the rank of the card decides
+        let mut hands = Vec::new();
+        if !folded {
+            for player_index in 0..self.player_amount {
+                hands.push(ShownHand { player_index, hand_rank: deck_get_rank(self.private_hands[player_index][0]) as u16, category: HandCategory::HighCard });
+            }
+        }
+
+        let winner_index = (0..self.player_amount).find(|&player_index| payoffs[player_index] > 0).unwrap();
+        let eligible_players = (0..self.player_amount).filter(|&player_index| !folded || player_index == winner_index).collect::<SmallVec<[usize; MAX_PLAYERS]>>();
+        let amount = self.bets.iter().sum::<usize>() as u32;
+
+        return ShowdownResult { pots: vec![PotResult { amount, eligible_players, winners: smallvec![(winner_index, amount)] }], hands, payoffs }
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::fmt::Debug;
 
 use hand_isomorphism_rust::deck::card_from_string;
@@ -9,6 +10,8 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use rand::rngs::StdRng;
 use rand::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 use smallvec::smallvec;
 use smallvec::SmallVec;
 
@@ -17,9 +20,16 @@ use crate::constants::MAX_PLAYERS;
 use crate::constants::NO_CARD_PLACEHOLDER;
 use crate::constants::PRIVATE_CARD_AMOUNT;
 use crate::constants::ROUNDS;
-use crate::game_states::base_game_state::GameState;
+use crate::game_states::base_game_state::{check_zero_sum, GameState};
+use crate::game_states::nlth_poker::game_state::format_cards;
+use crate::game_states::observation::push_one_hot;
+use crate::game_states::observation::ObservationTensors;
+use crate::game_states::observation::TensorShape;
+use crate::game_states::nlth_poker::rank::HandCategory;
+use crate::game_states::showdown::{split_pot, PotResult, Showdown, ShowdownResult, ShownHand};
 use crate::structs::ActionType;
 use crate::structs::Action;
+use crate::structs::BetSizing;
 
 lazy_static! {
     static ref CARD_RANKS: HashMap<String, i32> = {
@@ -50,7 +60,7 @@ lazy_static! {
     };
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LPGameState {
     pub player_amount: usize,
     pub round: usize,
@@ -170,20 +180,20 @@ impl GameState for LPGameState {
         if bet_raise_amount > 0 {            
             // If there were less than 2 raises we can still raise more
             if bet_raise_amount < 2 {
-                return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Bet, raise_amount: 0 }]
+                return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }]
             }
 
-            return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0 }, Action { action_type: ActionType::Call, raise_amount: 0 }]
+            return smallvec![Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }]
         }
 
-        return smallvec![Action { action_type: ActionType::Call, raise_amount: 0 }, Action { action_type: ActionType::Bet, raise_amount: 0 }];
+        return smallvec![Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }, Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction }];
     }
 
     fn is_terminal(&self) -> bool {
         // If anyone folded at any point, it's terminal
         if self.history.iter()
             .map(|round_history| {
-                if round_history.iter().contains(&Action { action_type: ActionType::Fold, raise_amount: 0 }) {
+                if round_history.iter().contains(&Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }) {
                     return true
                 }
                 return false
@@ -217,7 +227,7 @@ impl GameState for LPGameState {
 
     fn get_payoffs(&self) -> [i32; MAX_PLAYERS] {
         // All but 1 folded
-        if self.history.concat().contains(&Action { action_type: ActionType::Fold, raise_amount: 0 }) {
+        if self.history.concat().contains(&Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }) {
             let mut folded_player_index: usize = usize::MIN;
             for i in 0..self.history.len() {
                 for (j, action) in self.history[i].iter().enumerate() {
@@ -241,7 +251,7 @@ impl GameState for LPGameState {
                 return 0
             }).collect::<Vec<i32>>().try_into().unwrap();
 
-            return payoffs;
+            return check_zero_sum(payoffs);
         }
 
         // Tie
@@ -292,10 +302,12 @@ impl GameState for LPGameState {
             return payoff;
         }).collect::<Vec<i32>>().try_into().unwrap();
 
-        return payoffs;
+        return check_zero_sum(payoffs);
     }
 
     fn handle_action(&self, action: Action) -> Self {
+        // Leduc poker stores checks as calls and raises as bets
+        let action = action.without_check_raise();
         let mut new_bets = self.bets.clone();
         let active_player_index = self.get_active_player_index();
 
@@ -339,7 +351,7 @@ impl GameState for LPGameState {
 impl LPGameState {
     // Returns True if all players checked in the current round
     fn all_players_checked(&self) -> bool {
-        let num_checked = self.history[self.round].iter().filter(|&action| action == &Action { action_type: ActionType::Call, raise_amount: 0 }).count();
+        let num_checked = self.history[self.round].iter().filter(|&action| action == &Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }).count();
 
         return num_checked == self.player_amount && !self.history[self.round].iter().any(|action| action.action_type == ActionType::Bet)
     }
@@ -360,3 +372,114 @@ impl LPGameState {
         return false
     }
 }
+
+impl fmt::Display for LPGameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pot = self.bets.iter().map(|round_bets| round_bets.iter().sum::<u32>()).sum::<u32>();
+        writeln!(f, "Leduc, round {}, pot {}{}", self.round + 1, pot, if self.is_terminal() { " (terminal)" } else { "" })?;
+        // The community card is revealed in the second round
+        if self.round == 1 || self.is_terminal() {
+            writeln!(f, "Board: {}", format_cards(&self.community_cards[..1]))?;
+        }
+
+        writeln!(f, "  Player  Card  Bets")?;
+        for player_index in 0..self.player_amount {
+            writeln!(
+                f, "{} P{:<5}  {:<4}  {}",
+                if player_index == self.get_active_player_index() && !self.is_terminal() { ">" } else { " " },
+                player_index,
+                format_cards(&self.private_hands[player_index][..1]),
+                self.bets[0][player_index] + self.bets[1][player_index],
+            )?;
+        }
+
+        for round in 0..=self.round {
+            let actions = self.history[round].iter().map(|action| action.as_string()).collect::<Vec<String>>();
+            writeln!(f, "Round {}: {}", round + 1, actions.join(", "))?;
+        }
+
+        return Ok(())
+    }
+}
+
+// Rank of the jack, the lowest card in the deck
+const JACK_RANK: u8 = 9;
+// Ante of 1 plus two raises of 2 in the first round and two raises of 4 in the second round
+const MAX_CONTRIBUTION: f32 = 13.0;
+// Check, bet, raise and call
+const MAX_ROUND_HISTORY_LENGTH: usize = 4;
+
+/*
+    Observation tensor (12 values):
+        [0, 2)   observing player, one-hot
+        [2, 5)   rank of the private card, one-hot in the order J, Q, K
+        [5, 8)   rank of the community card once it is revealed in the second round, one-hot
+        [8, 10)  round, one-hot
+        [10, 12) chips put in by each player, divided by the maximum possible contribution of 13
+    Information state tensor (36 values): the observation followed by
+        [12, 36) per round up to 4 actions, each one-hot as (fold, check/call, bet/raise)
+*/
+impl ObservationTensors for LPGameState {
+    fn tensor_shape() -> TensorShape {
+        return TensorShape { information_state: 12 + 2 * MAX_ROUND_HISTORY_LENGTH * 3, observation: 12 }
+    }
+
+    fn observation_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = Vec::with_capacity(Self::tensor_shape().observation);
+        push_one_hot(&mut tensor, 2, Some(player_index));
+        push_one_hot(&mut tensor, 3, Some((deck_get_rank(self.private_hands[player_index][0]) - JACK_RANK) as usize));
+        let community_card_rank = if self.round == 1 { Some((deck_get_rank(self.community_cards[0]) - JACK_RANK) as usize) } else { None };
+        push_one_hot(&mut tensor, 3, community_card_rank);
+        push_one_hot(&mut tensor, 2, Some(self.round));
+        for player_bets_index in 0..2 {
+            tensor.push((self.bets[0][player_bets_index] + self.bets[1][player_bets_index]) as f32 / MAX_CONTRIBUTION);
+        }
+        return tensor
+    }
+
+    fn information_state_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = self.observation_tensor(player_index);
+        for round in 0..2 {
+            for history_index in 0..MAX_ROUND_HISTORY_LENGTH {
+                let action_index = self.history[round].get(history_index).map(|action| {
+                    match action.action_type {
+                        ActionType::Fold => return 0,
+                        ActionType::Bet => return 2,
+                        _ => return 1,
+                    }
+                });
+                push_one_hot(&mut tensor, 3, action_index);
+            }
+        }
+        return tensor
+    }
+}
+
+impl Showdown for LPGameState {
+    fn get_showdown_result(&self) -> ShowdownResult {
+        let payoffs = self.get_payoffs();
+        let folded = self.history.iter().flatten().any(|action| action.action_type == ActionType::Fold);
+
+        let mut hands = Vec::new();
+        if !folded {
+            for player_index in 0..self.player_amount {
+                let private_rank = deck_get_rank(self.private_hands[player_index][0]);
+                let player_cards_string = [self.private_hands[player_index][0], self.community_cards[0]].iter()
+                    .map(|&card| card_to_string(card).chars().next().unwrap())
+                    .collect::<String>();
+                // CARD_RANKS has 1 for the best hand, turn it around so a higher rank is better
+                let hand_rank = (CARD_RANKS.len() as i32 + 1 - CARD_RANKS[&player_cards_string]) as u16;
+                let category = if private_rank == deck_get_rank(self.community_cards[0]) { HandCategory::OnePair } else { HandCategory::HighCard };
+                hands.push(ShownHand { player_index, hand_rank, category });
+            }
+        }
+
+        // The loser of a showdown or the player that folded is the only one with a negative payoff
+        let eligible_players = (0..self.player_amount).filter(|&player_index| !folded || payoffs[player_index] > 0).collect::<SmallVec<[usize; MAX_PLAYERS]>>();
+        let winning_player_indices = (0..self.player_amount).filter(|&player_index| payoffs[player_index] >= 0).collect::<SmallVec<[usize; MAX_PLAYERS]>>();
+        let amount = self.bets.iter().flatten().sum::<u32>();
+        let winners = split_pot(amount, &winning_player_indices, 0..self.player_amount);
+
+        return ShowdownResult { pots: vec![PotResult { amount, eligible_players, winners }], hands, payoffs }
+    }
+}
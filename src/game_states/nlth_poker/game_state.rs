@@ -1,25 +1,44 @@
+use std::fmt;
 
 use itertools::Itertools;
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use hand_isomorphism_rust::deck::{card_from_string, Card, RANK_TO_CHAR, SUIT_TO_CHAR};
+use hand_isomorphism_rust::deck::{card_from_string, card_to_string, Card, RANK_TO_CHAR, SUIT_TO_CHAR};
 use smallvec::{smallvec, SmallVec};
 
-use crate::constants::{COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT, ROUNDS};
-use crate::game_states::base_game_state::GameState;
-use crate::structs::{ActionType, Action};
-use super::rank::rank_hand;
+use crate::constants::{BOARD_CARD_AMOUNTS, COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT, ROUNDS};
+use crate::game_states::base_game_state::{check_zero_sum, GameState};
+use crate::game_states::observation::{push_one_hot, ObservationTensors, TensorShape};
+use crate::game_states::showdown::{split_pot, PotResult, Showdown, ShowdownResult, ShownHand};
+use crate::structs::{ActionType, Action, BetRounding, BetSizing};
+use super::hand_index::{get_hand_from_index, get_hand_index};
+use super::pot_manager::PotManager;
+use super::rank::{get_hand_category, rank_hand};
 
 const ROUND_PREFLOP: usize = 0;
 const _ROUND_FLOP: usize = 1;
 const _ROUND_TURN: usize = 2;
 const ROUND_RIVER: usize = 3;
+const ROUND_NAMES: [&str; ROUNDS] = ["Preflop", "Flop", "Turn", "River"];
 
 const STACK_SIZE: u32 = 10_000;
 const SMALL_BLIND: u32 = 50;
 const BIG_BLIND: u32 = 100;
 
-#[derive(Clone, Debug)]
+/*
+    When a pot is split and can't be divided evenly, the remaining chips go one at a time to the winners in seat order:
+        FirstLeftOfButton: starting at the first seat left of the button (the big blind in heads-up, the small blind otherwise)
+        FirstFromSeat: starting at the given seat
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OddChipRule {
+    #[default]
+    FirstLeftOfButton,
+    FirstFromSeat(usize),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NLTHGameState {
     pub round: usize, // Used for indexing so it's usize
     pub player_amount: usize, // Used for indexing so it's usize
@@ -29,22 +48,27 @@ pub struct NLTHGameState {
     pub stacks: [u32; MAX_PLAYERS],
     pub bets: [[u32; MAX_PLAYERS]; ROUNDS],
     pub minimum_raise_amount: u32,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
 
     pub history: [SmallVec<[Action; 200]>; ROUNDS],
     pub active_player_index: usize, // Used for indexing so it's usize
     pub folded_players: [bool; MAX_PLAYERS],
-    pub all_in_players: [i32; MAX_PLAYERS],
-    /*
-        Each pot contains a bet amount per player. Each time a bet is made, it gets added to the newest pot.
-        Initially there is just a single (main) pot.
-        When a player goes all-in, a new pot created.
-        It's important that for every pot we still keep track of which player made which bets. This way we can divide them evenly later.
-    */
-    pub pots: [[u32; MAX_PLAYERS]; MAX_PLAYERS + 1], // There cannot be more than MAX_PLAYERS+1 pots
-    pub current_round_pot_all_in_amounts: [u32; MAX_PLAYERS + 1],
-    pub current_pot: usize, // Used for indexing so it's usize
+    pub all_in_players: [bool; MAX_PLAYERS],
+    // The main pot and side pots, rebuilt from the contributions of every player after each action
+    pub pot_manager: PotManager,
     // Keeping track of active_player_amount in a variable is quicker than performing the necessary Vec loops to get this number each time
     pub active_player_amount: u8,
+    // Who gets the chips that are left over when a pot is split
+    #[serde(default)]
+    pub odd_chip_rule: OddChipRule,
+    // Which players acted in the current round, an all-in smaller than a full raise does not reopen the betting for them
+    #[serde(default)]
+    pub players_acted: [bool; MAX_PLAYERS],
+    // How bet sizes are rounded to chips, see resolve_bet_amount
+    #[serde(default)]
+    pub bet_rounding: BetRounding,
 }
 
 impl GameState for NLTHGameState {
@@ -87,50 +111,11 @@ impl GameState for NLTHGameState {
             community_cards = [NO_CARD_PLACEHOLDER; 5];
         }
 
-        let blinds = (0..MAX_PLAYERS).map(|player_index| {
-            if player_index == 0 {
-                return SMALL_BLIND
-            } else if player_index == 1 {
-                return BIG_BLIND
-            }
-            return 0
-        }).collect::<Vec<_>>();
-
-        return NLTHGameState {
-            round: ROUND_PREFLOP,
-            player_amount,
-
-            private_hands,
-            community_cards,
-            stacks: (0..MAX_PLAYERS).map(|i| STACK_SIZE - blinds[i]).collect::<Vec<u32>>().try_into().unwrap(),
-            bets: [
-                (0..MAX_PLAYERS).map(|i| blinds[i]).collect::<Vec<u32>>().try_into().unwrap(),
-                [0; 6], // Flop
-                [0; 6], // Turn
-                [0; 6], // River
-            ],
-            minimum_raise_amount: BIG_BLIND,
+        let mut game_state = NLTHGameState::new_with_table(player_amount, [STACK_SIZE; MAX_PLAYERS], SMALL_BLIND, BIG_BLIND, 0);
+        game_state.private_hands = private_hands;
+        game_state.community_cards = community_cards;
 
-            history: [
-                SmallVec::new(), SmallVec::new(), SmallVec::new(), SmallVec::new()
-            ],
-            // In headsup poker, the small blind acts first preflop. Postflop the big blind acts first
-            // In 3+ player poker, in the preflop round the FTA is the player after the big blind, so in our case player at index 2 (player 3)
-            active_player_index: if player_amount == 2 { 0 } else { 2 },
-            folded_players: [false; MAX_PLAYERS],
-            all_in_players: [-1; MAX_PLAYERS],
-            pots: (0..MAX_PLAYERS+1).map(|i| {
-                (0..MAX_PLAYERS).map(|j| {
-                    if i == 0 {
-                        return blinds[j];
-                    }
-                    return 0
-                }).collect::<Vec<u32>>().try_into().unwrap()
-            }).collect::<Vec<[u32; MAX_PLAYERS]>>().try_into().unwrap(),
-            current_round_pot_all_in_amounts: [0; MAX_PLAYERS+1],
-            current_pot: 0,
-            active_player_amount: player_amount as u8
-        }
+        return game_state
     }
 
     fn get_total_rounds() -> usize {
@@ -190,25 +175,28 @@ impl GameState for NLTHGameState {
     }
 
     fn get_active_player_actions(&self, bets_in_abstraction_option: Option<&SmallVec<[Action; 40]>>) -> SmallVec<[Action; 40]> {
-        let pot = self.get_total_pot();
         let mut actions_in_abstraction: SmallVec<[Action; 40]> = smallvec![
-            Action {action_type: ActionType::Fold, raise_amount: 0 },
-            Action {action_type: ActionType::Call, raise_amount: 0 },
-            Action {action_type: ActionType::AllIn, raise_amount: 0 },
+            Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction },
         ];
 
         if let Some(bets_in_abstraction) = bets_in_abstraction_option {
             actions_in_abstraction.extend(bets_in_abstraction.clone())
         }
 
+        let can_raise = self.can_raise();
         return actions_in_abstraction.into_iter().filter_map(|action| {
-            // Going all-in is always an option
+            let call_amount = self.get_call_amount();
+
+            // Going all-in is always an option, unless it would be a raise while the betting isn't open to the player
             if action.action_type == ActionType::AllIn {
+                if !can_raise && self.stacks[self.active_player_index] > call_amount {
+                    return None;
+                }
                 return Some(action);
             };
 
-            let call_amount = self.get_call_amount();
-
             if action.action_type == ActionType::Fold {
                 if call_amount == 0 {
                     // We shouldn't have the option to fold if we don't need to call any amount
@@ -225,8 +213,11 @@ impl GameState for NLTHGameState {
                 return None;
             };
 
-            let current_bets = self.bets[self.round][self.active_player_index];
-            let extra_bets = ((pot + call_amount) as f32 * action.get_multiplier()) as u32 - current_bets;
+            if !can_raise {
+                return None;
+            }
+
+            let extra_bets = resolve_bet_amount(self, &action);
 
             // The the new extra bets minus the call amount should be high or higher than the minimum raise amount
             if extra_bets < call_amount || extra_bets - call_amount < self.minimum_raise_amount {
@@ -267,79 +258,7 @@ impl GameState for NLTHGameState {
     }
 
     fn get_payoffs(&self) -> [i32; MAX_PLAYERS] {
-        // All but 1 folded. No need to deal with all-ins and multiple pots because if there was an all-in not everyone has folded
-        if self.folded_players.iter().filter(|&value| value == &true).count() == self.player_amount-1 {
-            let winning_player_index = self.folded_players.iter().enumerate().find(|(_, &value)| value == false).unwrap().0;
-            
-            let payoffs: [i32; MAX_PLAYERS] = (0..MAX_PLAYERS).map(|player_index| {
-                if player_index > self.player_amount { return 0 }
-
-                if player_index == winning_player_index {
-                    return self.get_total_pot() as i32 - self.bets.iter().map(|round_bets| round_bets[winning_player_index]).sum::<u32>() as i32;
-                }
-
-                return -self.pots.iter().map(|round_pots| round_pots[player_index] as i32).sum::<i32>();
-            }).collect::<Vec<i32>>().try_into().unwrap();
-
-            return payoffs;
-        }
-
-        // // Showdown // //
-        let mut payoffs = [0; MAX_PLAYERS];
-
-        // Iterate through all pots and divide them amongst eligible players
-        for pot in self.pots.iter(){
-            let pot_sum = pot.iter().sum::<u32>();
-            // Decide who can contest the pot
-            let participating_player_indices = (0..self.player_amount).filter_map(|player_index| {
-                if
-                    player_index > self.player_amount-1 ||
-                    // Players that folded at any point cannot contest the pot (their losses will be calculated later)
-                    self.folded_players[player_index]
-                { return None; }
-
-                return Some(player_index);
-            }).collect::<Vec<usize>>();
-
-            if participating_player_indices.len() == 0 {
-                continue
-            }
-
-            // Calculate hand ranks for the players that can contest this pot
-            let player_hand_ranks = (0..self.player_amount).map(|player_index| {
-                if participating_player_indices.contains(&player_index) {
-                    let mut hand = self.private_hands[player_index].to_vec();
-                    hand.extend(self.community_cards.clone());
-                    let rank = rank_hand(hand);
-                    return rank
-                }
-                return 0;
-            }).collect::<Vec<_>>();
-
-            let highest_hand_rank = player_hand_ranks.iter().max().unwrap();
-            // Grab the index of the players with the highest rank. This way we account for ties
-            let winning_player_indices = participating_player_indices.iter()
-                .filter(|&player_index| &player_hand_ranks[*player_index] == highest_hand_rank)
-                .map(|&player_index| player_index)
-                .collect::<Vec<_>>();
-
-            for player_index in participating_player_indices {
-                if winning_player_indices.contains(&player_index) {
-                    payoffs[player_index] += (pot_sum / winning_player_indices.len() as u32) as i32 - pot[player_index] as i32
-                } else {
-                    payoffs[player_index] -= pot[player_index] as i32
-                }
-            }
-        }
-
-        // Calculate losses for players who folded as they were not accounted for in the pot division
-        for player_index in 0..self.player_amount {
-            if self.folded_players[player_index] {
-                payoffs[player_index] -= self.pots.iter().map(|pot| pot[player_index]).sum::<u32>() as i32
-            }
-        }
-
-        return payoffs;
+        return self.get_showdown_result().payoffs;
     }
 
     fn can_proceed_to_next_round(&self) -> bool {
@@ -351,6 +270,26 @@ impl GameState for NLTHGameState {
     }
 
     fn handle_action(&self, action: Action) -> Self {
+        let mut extra_bets = 0;
+        if action.is_bet_raise() {
+            extra_bets = resolve_bet_amount(self, &action);
+        }
+
+        return self.apply_action(action, extra_bets);
+    }
+
+    fn get_bet_commitment(&self, action: &Action) -> Option<(u32, u32)> {
+        return Some((resolve_bet_amount(self, action), self.stacks[self.active_player_index]))
+    }
+}
+
+impl NLTHGameState {
+    /*
+        Applies the action to a copy of the state.
+        `bet_extra_bets` is only used for bets and raises: it is the amount that goes from the stack into the pot, including the call amount.
+    */
+    fn apply_action(&self, action: Action, bet_extra_bets: u32) -> Self {
+        let action = self.classify_action(action);
         let mut next_state = self.clone();
 
         if action.action_type == ActionType::Fold {
@@ -359,75 +298,26 @@ impl GameState for NLTHGameState {
             // The player becomes inactive from this point on
             next_state.active_player_amount -= 1;
         } else {
-            let current_bets = next_state.bets[next_state.round][next_state.active_player_index];
             let call_amount = next_state.get_call_amount();
             let mut extra_bets = call_amount;
 
             if action.action_type == ActionType::AllIn {
                 extra_bets = next_state.stacks[next_state.active_player_index];
 
-                // The all-in is added to the current pot like normal, and a new pot is created
-                let mut pot_bets_left = extra_bets;
-                for (pot_index, &all_in_amount) in next_state.current_round_pot_all_in_amounts.iter().enumerate() {
-                    if all_in_amount > current_bets {
-                        let pot_bets = (all_in_amount - current_bets).min(pot_bets_left);
-                        next_state.pots[pot_index][next_state.active_player_index] += pot_bets;
-                        pot_bets_left -= pot_bets;
-                    }
-                }
-                next_state.current_round_pot_all_in_amounts[next_state.current_pot] = pot_bets_left + next_state.pots[next_state.current_pot][next_state.active_player_index];
-                next_state.pots[next_state.current_pot][next_state.active_player_index] += pot_bets_left;
-
-                // The all-in is more than the minimum raise amount
-                if call_amount < next_state.stacks[next_state.active_player_index] && next_state.stacks[next_state.active_player_index] - call_amount > self.minimum_raise_amount {
+                // The all-in is a full raise. A smaller all-in leaves the minimum raise at the last full raise
+                if call_amount < extra_bets && extra_bets - call_amount >= self.minimum_raise_amount {
                     // Set the minimum raise amount to the all-in amount
-                    next_state.minimum_raise_amount = next_state.stacks[next_state.active_player_index] - call_amount;
-                } else {
-                    // Divide any money up to this point that goes above the all-in amount in the next pots
-                    let mut new_pot_amounts = [0; MAX_PLAYERS];
-                    for (player_index, bets) in next_state.pots[next_state.current_pot].iter_mut().enumerate() {
-                        let existing_bets = bets.clone();
-                        if existing_bets > next_state.stacks[next_state.active_player_index] {
-                            let bets_to_redistribute = existing_bets - next_state.stacks[next_state.active_player_index];
-                            new_pot_amounts[player_index] += bets_to_redistribute;
-                            *bets -= bets_to_redistribute;
-                        }
-                    }
-                    next_state.pots[next_state.current_pot+1] = new_pot_amounts;
+                    next_state.minimum_raise_amount = extra_bets - call_amount;
                 }
 
-                // Set the value on the index of the active player to the current pot
-                next_state.all_in_players[next_state.active_player_index] = next_state.current_pot as i32;
-
-                next_state.current_pot += 1;
+                next_state.all_in_players[next_state.active_player_index] = true;
 
                 // The player becomes inactive from this point on
                 next_state.active_player_amount -= 1;
-            } else {
-                if action.is_bet_raise() {
-                    let current_pot = next_state.get_total_pot();
-                    let current_bets = next_state.bets[next_state.round][next_state.active_player_index];
-                    extra_bets = ((current_pot + call_amount) as f32 * action.get_multiplier()) as u32 - current_bets;
-
-                    next_state.minimum_raise_amount = extra_bets - call_amount
-                }
+            } else if action.is_bet_raise() {
+                extra_bets = bet_extra_bets;
 
-                /*
-                    Managing sidepots
-                    If players have gone all-in this round, they are entitled to a portion of the pot up until the amount they went all-in with.
-                    So we divide the extra_bets amongst the different pots that may be active at the moment.
-                    The remainder gets sent to the main pot.
-                */
-                let mut pot_bets_left = extra_bets;
-                // Add the new wager to the current pot, managing any sidepots that may be active
-                for (pot_index, &all_in_amount) in next_state.current_round_pot_all_in_amounts.iter().enumerate() {
-                    if all_in_amount > current_bets {
-                        let pot_bets = (all_in_amount - current_bets).min(pot_bets_left);
-                        next_state.pots[pot_index][next_state.active_player_index] += pot_bets;
-                        pot_bets_left -= pot_bets;
-                    }
-                }
-                next_state.pots[next_state.current_pot][next_state.active_player_index] += pot_bets_left;
+                next_state.minimum_raise_amount = extra_bets - call_amount
             }
 
             // Decrease the wager from the player's stack
@@ -436,13 +326,19 @@ impl GameState for NLTHGameState {
             next_state.bets[next_state.round][next_state.active_player_index] += extra_bets;
         }
 
+        // The side pots follow from how much every player put in, which players folded and which went all-in
+        let contributions = next_state.get_contributions();
+        let player_amount = next_state.player_amount;
+        next_state.pot_manager.update(&contributions[..player_amount], &next_state.folded_players[..player_amount], &next_state.all_in_players[..player_amount]);
+
         next_state.history[next_state.round].push(action);
+        next_state.players_acted[next_state.active_player_index] = true;
 
         // Set the new active player index
         let mut current_new_active_player_index = (next_state.active_player_index + 1) % next_state.player_amount;
         for _ in 0..next_state.player_amount {
             // The player should not have folded or have gone all-in in order to be active
-            if !next_state.folded_players[current_new_active_player_index] && next_state.all_in_players[current_new_active_player_index] == -1 {
+            if !next_state.folded_players[current_new_active_player_index] && !next_state.all_in_players[current_new_active_player_index] {
                 break;
             }
             current_new_active_player_index = (current_new_active_player_index + 1) % next_state.player_amount;
@@ -453,9 +349,8 @@ impl GameState for NLTHGameState {
         if next_state.can_proceed_to_next_round() {
             // Transition to next round
             next_state.round += 1;
-            next_state.minimum_raise_amount = BIG_BLIND;
-            // We don't have to keep track of sidepots created during this round anymore
-            next_state.current_round_pot_all_in_amounts = [0; MAX_PLAYERS+1];
+            next_state.minimum_raise_amount = next_state.big_blind;
+            next_state.players_acted = [false; MAX_PLAYERS];
             if next_state.player_amount == 2 {
                 // In heads-up poker the big blind (player 2) acts first post-flop
                 next_state.active_player_index = 1;
@@ -463,15 +358,214 @@ impl GameState for NLTHGameState {
                 // Otherwise the small blind (player 1) acts first
                 next_state.active_player_index = 0;
             }
+            // Skip players that folded or went all-in
+            for _ in 0..next_state.player_amount {
+                if !next_state.folded_players[next_state.active_player_index] && !next_state.all_in_players[next_state.active_player_index] {
+                    break;
+                }
+                next_state.active_player_index = (next_state.active_player_index + 1) % next_state.player_amount;
+            }
         }
 
         return next_state;
     }
-}
 
-impl NLTHGameState {
+    /*
+        Creates a hand without cards for an arbitrary table: per-player starting stacks, blinds and an ante paid by every player.
+        Player 0 posts the small blind and player 1 the big blind, just like in `new_empty`.
+        Antes go straight into the main pot and are not counted towards the preflop bets, so they don't change the call amount.
+    */
+    pub fn new_with_table(player_amount: usize, stacks: [u32; MAX_PLAYERS], small_blind: u32, big_blind: u32, ante: u32) -> Self {
+        let blinds = (0..MAX_PLAYERS).map(|player_index| {
+            if player_index == 0 {
+                return small_blind
+            } else if player_index == 1 {
+                return big_blind
+            }
+            return 0
+        }).collect::<Vec<_>>();
+        let antes = (0..MAX_PLAYERS).map(|player_index| {
+            if player_index < player_amount {
+                return ante
+            }
+            return 0
+        }).collect::<Vec<_>>();
+
+        let contributions = (0..player_amount).map(|player_index| blinds[player_index] + antes[player_index]).collect::<Vec<u32>>();
+        let pot_manager = PotManager::from_contributions(&contributions, &[false; MAX_PLAYERS][..player_amount], &[false; MAX_PLAYERS][..player_amount]);
+
+        return NLTHGameState {
+            round: ROUND_PREFLOP,
+            player_amount,
+
+            private_hands: [[NO_CARD_PLACEHOLDER; PRIVATE_CARD_AMOUNT]; MAX_PLAYERS],
+            community_cards: [NO_CARD_PLACEHOLDER; COMMUNITY_CARD_AMOUNT],
+            stacks: (0..MAX_PLAYERS).map(|i| stacks[i] - blinds[i] - antes[i]).collect::<Vec<u32>>().try_into().unwrap(),
+            bets: [
+                (0..MAX_PLAYERS).map(|i| blinds[i]).collect::<Vec<u32>>().try_into().unwrap(),
+                [0; 6], // Flop
+                [0; 6], // Turn
+                [0; 6], // River
+            ],
+            minimum_raise_amount: big_blind,
+            small_blind,
+            big_blind,
+            ante,
+
+            history: [
+                SmallVec::new(), SmallVec::new(), SmallVec::new(), SmallVec::new()
+            ],
+            // In headsup poker, the small blind acts first preflop. Postflop the big blind acts first
+            // In 3+ player poker, in the preflop round the FTA is the player after the big blind, so in our case player at index 2 (player 3)
+            active_player_index: if player_amount == 2 { 0 } else { 2 },
+            folded_players: [false; MAX_PLAYERS],
+            all_in_players: [false; MAX_PLAYERS],
+            pot_manager,
+            active_player_amount: player_amount as u8,
+            odd_chip_rule: OddChipRule::default(),
+            players_acted: [false; MAX_PLAYERS],
+            bet_rounding: BetRounding::default(),
+        }
+    }
+
+    /*
+        Bets or raises an exact amount of chips instead of a multiplier of the pot. Used to replay actions that are not part of any abstraction (e.g. hand histories).
+        `extra_bets` is the amount the active player moves from their stack into the pot, so it includes the call amount.
+        The action stored in the history is the chip amount the player raises to, or the closest pot multiplier when that doesn't fit a raise_amount.
+    */
+    pub fn handle_bet_amount(&self, extra_bets: u32) -> Self {
+        let call_amount = self.get_call_amount();
+        let current_bets = self.bets[self.round][self.active_player_index];
+        let action = match u16::try_from(current_bets + extra_bets) {
+            Ok(raise_to) => Action { action_type: ActionType::Bet, raise_amount: raise_to, sizing: BetSizing::Chips },
+            Err(_) => {
+                let multiplier = ((current_bets + extra_bets) as f32 / (self.get_total_pot() + call_amount) as f32 * 100.0).round();
+                Action { action_type: ActionType::Bet, raise_amount: multiplier.min(u16::MAX as f32) as u16, sizing: BetSizing::PotFraction }
+            },
+        };
+
+        return self.apply_action(action, extra_bets);
+    }
+
+    /*
+        Bets or raises an exact amount of chips, but stores `action` in the history. Used by action translation,
+        so the history follows the abstraction while the pot and stacks follow the real game.
+    */
+    pub fn handle_translated_bet(&self, action: Action, extra_bets: u32) -> Self {
+        return self.apply_action(action, extra_bets);
+    }
+
+    // The chips every player put in this hand, including blinds and antes
+    pub fn get_contributions(&self) -> [u32; MAX_PLAYERS] {
+        return std::array::from_fn(|player_index| {
+            if player_index >= self.player_amount {
+                return 0
+            }
+            return self.ante + self.bets.iter().map(|round_bets| round_bets[player_index]).sum::<u32>()
+        })
+    }
+
+    // The main pot and side pots with the players that can win them
+    pub fn get_pot_manager(&self) -> &PotManager {
+        return &self.pot_manager
+    }
+
+    // The suit isomorphic index of the hole cards of a player and the board of the current round
+    pub fn get_hand_index(&self, player_index: usize) -> Result<u64, String> {
+        let cards = self.private_hands[player_index].iter()
+            .chain(self.community_cards[..BOARD_CARD_AMOUNTS[self.round]].iter())
+            .copied()
+            .collect::<SmallVec<[Card; PRIVATE_CARD_AMOUNT + COMMUNITY_CARD_AMOUNT]>>();
+        if cards.contains(&NO_CARD_PLACEHOLDER) {
+            return Err(format!("Player {} has no cards", player_index))
+        }
+        return get_hand_index(self.round, &cards)
+    }
+
+    // Hole cards and a board of a round that have the given hand index, the inverse of get_hand_index
+    pub fn get_hand_from_index(round: usize, hand_index: u64) -> Result<([Card; PRIVATE_CARD_AMOUNT], SmallVec<[Card; COMMUNITY_CARD_AMOUNT]>), String> {
+        let cards = get_hand_from_index(round, hand_index)?;
+        return Ok(([cards[0], cards[1]], cards[PRIVATE_CARD_AMOUNT..].iter().copied().collect()))
+    }
+
+    /*
+        The payoffs averaged over every way the rest of the board can come, for terminal states where everyone went all-in before the river.
+        The community cards that were already dealt and the hole cards of every player are kept, side pots are settled like in get_payoffs.
+        When there are no more than `samples` runouts they are all played out, otherwise `samples` random runouts are.
+        A hand that ended on the river or with everyone but one player folding has a single outcome, its payoffs are returned as they are.
+    */
+    pub fn expected_payoffs(&self, samples: u64, rng_seed: Option<u64>) -> Result<[f64; MAX_PLAYERS], String> {
+        if !self.is_terminal() {
+            return Err("Expected payoffs need a terminal state".to_string())
+        }
+        if samples == 0 {
+            return Err("Expected payoffs need at least one sample".to_string())
+        }
+        let dealt_card_amount = BOARD_CARD_AMOUNTS[self.round];
+        let remaining_player_amount = self.folded_players[..self.player_amount].iter().filter(|&&folded| !folded).count();
+        if dealt_card_amount == COMMUNITY_CARD_AMOUNT || remaining_player_amount < 2 {
+            return Ok(self.get_payoffs().map(|payoff| payoff as f64))
+        }
+
+        let known_cards = self.community_cards[..dealt_card_amount].iter()
+            .chain(self.private_hands[..self.player_amount].iter().flatten())
+            .filter(|&&card| card != NO_CARD_PLACEHOLDER)
+            .fold(0u64, |known_cards, &card| known_cards | (1 << card));
+        let deck = (0..52).filter(|&card| known_cards & (1 << card) == 0).collect::<Vec<Card>>();
+        let missing_card_amount = COMMUNITY_CARD_AMOUNT - dealt_card_amount;
+        let runout_amount = (0..missing_card_amount as u64).fold(1u64, |total, i| total * (deck.len() as u64 - i) / (i + 1));
+
+        let mut game_state = self.clone();
+        let mut total_payoffs = [0.0; MAX_PLAYERS];
+        let mut played_runouts = 0;
+        let mut play_runout = |runout: &[Card]| {
+            game_state.community_cards[dealt_card_amount..].copy_from_slice(runout);
+            for (total_payoff, payoff) in total_payoffs.iter_mut().zip(game_state.get_payoffs()) {
+                *total_payoff += payoff as f64;
+            }
+            played_runouts += 1;
+        };
+        if runout_amount <= samples {
+            for runout in deck.iter().copied().combinations(missing_card_amount) {
+                play_runout(&runout);
+            }
+        } else {
+            let mut rng = if let Some(seed) = rng_seed {
+                StdRng::seed_from_u64(seed)
+            } else {
+                StdRng::seed_from_u64(thread_rng().next_u64())
+            };
+            for _ in 0..samples {
+                let runout = deck.choose_multiple(&mut rng, missing_card_amount).copied().collect::<SmallVec<[Card; COMMUNITY_CARD_AMOUNT]>>();
+                play_runout(&runout);
+            }
+        }
+
+        return Ok(total_payoffs.map(|total_payoff| total_payoff / played_runouts as f64))
+    }
+
+    // Every seat in the order in which they get odd chips
+    pub fn get_odd_chip_order(&self) -> impl Iterator<Item = usize> {
+        let player_amount = self.player_amount;
+        let first_player_index = match self.odd_chip_rule {
+            // The button is the small blind in heads-up and the last seat otherwise, so the seat to its left is seat 1 or seat 0
+            OddChipRule::FirstLeftOfButton => if player_amount == 2 { 1 } else { 0 },
+            OddChipRule::FirstFromSeat(player_index) => player_index % player_amount,
+        };
+        return (0..player_amount).map(move |offset| (first_player_index + offset) % player_amount)
+    }
+
+    /*
+        Whether the active player may bet or raise. A player who already acted this round only gets to raise again when the bet went up
+        by at least a full raise since, possibly through several all-ins that were each too small to be a full raise themselves.
+        Since the player matched the highest bet when they acted, that is the case when the call amount is at least the last full raise.
+    */
+    pub fn can_raise(&self) -> bool {
+        return !self.players_acted[self.active_player_index] || self.get_call_amount() >= self.minimum_raise_amount
+    }
+
     pub fn get_total_pot(&self) -> u32 {
-        return self.pots.iter().map(|pot| pot.iter().sum::<u32>()).sum();
+        return self.pot_manager.get_total();
     }
 
     pub fn get_call_amount(&self) -> u32 {
@@ -485,11 +579,30 @@ impl NLTHGameState {
         return call_amount
     }
 
+    /*
+        Gives a call or check the type Check when there is nothing to call and Call otherwise,
+        and a bet or raise the type Bet when nobody has bet this round yet and Raise otherwise. The blinds count as bets.
+        Other actions are returned as they are.
+    */
+    pub fn classify_action(&self, action: Action) -> Action {
+        let action_type = match action.action_type {
+            ActionType::Check | ActionType::Call => {
+                if self.get_call_amount() == 0 { ActionType::Check } else { ActionType::Call }
+            },
+            ActionType::Bet | ActionType::Raise => {
+                if self.bets[self.round].iter().all(|&bets| bets == 0) { ActionType::Bet } else { ActionType::Raise }
+            },
+            action_type => action_type,
+        };
+        return Action { action_type, ..action }
+    }
+
     // Returns True if all remaining (not folded) players checked in the current round (noone bet or raised or went all-in)
     pub fn all_remaining_players_checked(&self) -> bool {
-        let num_checked = self.history[self.round].iter().filter(|&action| action.action_type == ActionType::Call).count();
+        // Calling the big blind counts as well
+        let num_checked = self.history[self.round].iter().filter(|&action| action.is_check_call()).count();
 
-        return num_checked == self.active_player_amount.into() &&
+        return num_checked == self.active_player_amount as usize &&
             !self.history[self.round].iter().any(|action| action.is_bet_raise() || action.action_type == ActionType::AllIn)
     }
 
@@ -503,7 +616,7 @@ impl NLTHGameState {
                 if self.history[self.round][reversed_index..]
                     .iter()
                     .filter(|&action| action.action_type != ActionType::Fold) // Exclude fold actions as they have impact on active_player_amount
-                    .count() == self.active_player_amount.into()
+                    .count() == self.active_player_amount as usize
                 {
                     return true
                 }
@@ -515,7 +628,7 @@ impl NLTHGameState {
                 if self.history[self.round][reversed_index..]
                     .iter()
                     .filter(|&action| action.action_type != ActionType::Fold) // Exclude fold actions as they have impact on active_player_amount
-                    .count() > self.active_player_amount.into()
+                    .count() > self.active_player_amount as usize
                 {
                     return true
                 }
@@ -525,3 +638,200 @@ impl NLTHGameState {
         return false
     }
 }
+
+/*
+    The amount of chips the active player moves into the pot for a bet or raise, including the call amount.
+    Every sizing resolves to the total bet of the round the player raises to, see BetSizing. The arithmetic is exact,
+    after which the amount is rounded with the bet rounding of the state. Chips are used as they are.
+    Returns 0 when the player already has that much in the pot this round.
+*/
+pub fn resolve_bet_amount(game_state: &NLTHGameState, action: &Action) -> u32 {
+    let current_bets = game_state.bets[game_state.round][game_state.active_player_index];
+    let raise_amount = action.raise_amount as u64;
+    let round = |base: u32| game_state.bet_rounding.round(base as u64 * raise_amount, 100, game_state.big_blind);
+    let raise_to = match action.sizing {
+        BetSizing::PotFraction => round(game_state.get_total_pot() + game_state.get_call_amount()),
+        BetSizing::BigBlinds => round(game_state.big_blind),
+        BetSizing::LastRaise => round(*game_state.bets[game_state.round].iter().max().unwrap()),
+        BetSizing::Chips => action.raise_amount as u32,
+    };
+    return raise_to.saturating_sub(current_bets)
+}
+
+// Formats cards like "AsKs", with "??" for cards that are not known
+pub fn format_cards(cards: &[Card]) -> String {
+    return cards.iter().map(|&card| {
+        if card == NO_CARD_PLACEHOLDER {
+            return "??".to_owned()
+        }
+        return card_to_string(card)
+    }).collect::<Vec<String>>().join("")
+}
+
+impl fmt::Display for NLTHGameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "NLTH {}-max, {}, pot {}{}", self.player_amount, ROUND_NAMES[self.round], self.get_total_pot(), if self.is_terminal() { " (terminal)" } else { "" })?;
+
+        // Only show the board cards that have been dealt, unless the hand is over
+        let dealt_card_amount = if self.is_terminal() { COMMUNITY_CARD_AMOUNT } else { BOARD_CARD_AMOUNTS[self.round] };
+        writeln!(f, "Board: {}", format_cards(&self.community_cards[..dealt_card_amount]))?;
+
+        writeln!(f, "  Player  Cards  {:>8}  {:>8}  Status", "Stack", "Bet")?;
+        for player_index in 0..self.player_amount {
+            let status = if self.folded_players[player_index] {
+                "folded"
+            } else if self.all_in_players[player_index] {
+                "all-in"
+            } else {
+                ""
+            };
+            let line = format!(
+                "{} P{:<5}  {:<5}  {:>8}  {:>8}  {}",
+                if player_index == self.active_player_index && !self.is_terminal() { ">" } else { " " },
+                player_index,
+                format_cards(&self.private_hands[player_index]),
+                self.stacks[player_index],
+                self.bets[self.round][player_index],
+                status,
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        for (pot_index, pot) in self.get_pot_manager().pots().iter().enumerate() {
+            let eligible_players = pot.eligible_players.iter().map(|player_index| format!("P{}", player_index)).collect::<Vec<String>>();
+            let pot_name = if pot_index == 0 { "Main pot".to_owned() } else { format!("Side pot {}", pot_index) };
+            writeln!(f, "{}: {} ({})", pot_name, pot.amount, eligible_players.join(", "))?;
+        }
+
+        for round in 0..=self.round {
+            let actions = self.history[round].iter().map(|action| action.as_string()).collect::<Vec<String>>();
+            writeln!(f, "{}: {}", ROUND_NAMES[round], actions.join(", "))?;
+        }
+
+        return Ok(())
+    }
+}
+
+const DECK_SIZE: usize = 52;
+const ACTION_TYPE_AMOUNT: usize = 4;
+// Actions per round that are part of the information state tensor, later actions are left out
+const MAX_TENSOR_ROUND_HISTORY_LENGTH: usize = 16;
+const OBSERVATION_TENSOR_SIZE: usize = MAX_PLAYERS * 3 + DECK_SIZE * 2 + ROUNDS + ROUNDS * MAX_PLAYERS + MAX_PLAYERS + 1 + MAX_PLAYERS;
+
+/*
+    Observation tensor (163 values), seats beyond the player amount are always 0:
+        [0, 6)     observing player, one-hot
+        [6, 12)    players that have not folded
+        [12, 18)   players that are all-in
+        [18, 70)   private cards of the observing player, indexed by card
+        [70, 122)  community cards that have been dealt in the current round, indexed by card
+        [122, 126) round, one-hot
+        [126, 150) bets of every player in every round, round major
+        [150, 156) stacks
+        [156, 157) total pot
+        [157, 163) active player, one-hot (all 0 once the game is terminal)
+    Chip amounts are divided by the average starting stack, so the tensors don't depend on the stack size of the table.
+    Information state tensor (483 values): the observation followed by
+        [163, 483) per round the first 16 actions, each as a one-hot over (fold, check/call, bet/raise, all-in) and the raise amount divided by 100
+*/
+impl ObservationTensors for NLTHGameState {
+    fn tensor_shape() -> TensorShape {
+        return TensorShape {
+            information_state: OBSERVATION_TENSOR_SIZE + ROUNDS * MAX_TENSOR_ROUND_HISTORY_LENGTH * (ACTION_TYPE_AMOUNT + 1),
+            observation: OBSERVATION_TENSOR_SIZE,
+        }
+    }
+
+    fn observation_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = Vec::with_capacity(Self::tensor_shape().observation);
+        let total_contributions = self.get_total_pot();
+        let average_starting_stack = (self.stacks.iter().sum::<u32>() + total_contributions) as f32 / self.player_amount as f32;
+
+        push_one_hot(&mut tensor, MAX_PLAYERS, Some(player_index));
+        for player_index in 0..MAX_PLAYERS {
+            tensor.push((player_index < self.player_amount && !self.folded_players[player_index]) as u8 as f32);
+        }
+        for player_index in 0..MAX_PLAYERS {
+            tensor.push((player_index < self.player_amount && self.all_in_players[player_index]) as u8 as f32);
+        }
+
+        let mut private_cards = [0.0; DECK_SIZE];
+        for &card in self.private_hands[player_index].iter().filter(|&&card| card != NO_CARD_PLACEHOLDER) {
+            private_cards[card as usize] = 1.0;
+        }
+        tensor.extend_from_slice(&private_cards);
+        let mut community_cards = [0.0; DECK_SIZE];
+        for &card in self.community_cards[..BOARD_CARD_AMOUNTS[self.round]].iter().filter(|&&card| card != NO_CARD_PLACEHOLDER) {
+            community_cards[card as usize] = 1.0;
+        }
+        tensor.extend_from_slice(&community_cards);
+
+        push_one_hot(&mut tensor, ROUNDS, Some(self.round));
+        for round_bets in self.bets.iter() {
+            tensor.extend(round_bets.iter().map(|&bets| bets as f32 / average_starting_stack));
+        }
+        tensor.extend(self.stacks.iter().map(|&stack| stack as f32 / average_starting_stack));
+        tensor.push(total_contributions as f32 / average_starting_stack);
+        push_one_hot(&mut tensor, MAX_PLAYERS, if self.is_terminal() { None } else { Some(self.active_player_index) });
+        return tensor
+    }
+
+    fn information_state_tensor(&self, player_index: usize) -> Vec<f32> {
+        let mut tensor = self.observation_tensor(player_index);
+        for round_history in self.history.iter() {
+            for history_index in 0..MAX_TENSOR_ROUND_HISTORY_LENGTH {
+                match round_history.get(history_index) {
+                    Some(action) => {
+                        let action_type_index = match action.action_type {
+                            ActionType::Fold => 0,
+                            ActionType::Check | ActionType::Call => 1,
+                            ActionType::Bet | ActionType::Raise => 2,
+                            ActionType::AllIn => 3,
+                        };
+                        push_one_hot(&mut tensor, ACTION_TYPE_AMOUNT, Some(action_type_index));
+                        tensor.push(action.raise_amount as f32 / 100.0);
+                    },
+                    None => push_one_hot(&mut tensor, ACTION_TYPE_AMOUNT + 1, None),
+                }
+            }
+        }
+        return tensor
+    }
+}
+
+impl Showdown for NLTHGameState {
+    fn get_showdown_result(&self) -> ShowdownResult {
+        // Hands are only ranked when there is a showdown, so a player that wins by everyone else folding doesn't need cards
+        let mut hands = Vec::new();
+        if self.folded_players[..self.player_amount].iter().filter(|&&folded| !folded).count() > 1 {
+            for player_index in (0..self.player_amount).filter(|&player_index| !self.folded_players[player_index]) {
+                let mut hand = [0; PRIVATE_CARD_AMOUNT + COMMUNITY_CARD_AMOUNT];
+                hand[..PRIVATE_CARD_AMOUNT].copy_from_slice(&self.private_hands[player_index]);
+                hand[PRIVATE_CARD_AMOUNT..].copy_from_slice(&self.community_cards);
+                let hand_rank = rank_hand(&hand);
+                hands.push(ShownHand { player_index, hand_rank, category: get_hand_category(hand_rank) });
+            }
+        }
+        let get_hand_rank = |player_index: usize| hands.iter().find(|hand| hand.player_index == player_index).map(|hand| hand.hand_rank);
+
+        // Every player loses what they put in and gets back what they win
+        let mut payoffs: [i32; MAX_PLAYERS] = self.get_contributions().map(|contribution| -(contribution as i32));
+        let mut pots = Vec::new();
+        for pot in self.get_pot_manager().pots() {
+            // Grab the players with the highest rank. This way we account for ties
+            let highest_hand_rank = pot.eligible_players.iter().map(|&player_index| get_hand_rank(player_index)).max().flatten();
+            let winning_player_indices = pot.eligible_players.iter()
+                .copied()
+                .filter(|&player_index| get_hand_rank(player_index) == highest_hand_rank)
+                .collect::<SmallVec<[usize; MAX_PLAYERS]>>();
+
+            let winners = split_pot(pot.amount, &winning_player_indices, self.get_odd_chip_order());
+            for &(player_index, amount) in winners.iter() {
+                payoffs[player_index] += amount as i32;
+            }
+            pots.push(PotResult { amount: pot.amount, eligible_players: pot.eligible_players.clone(), winners });
+        }
+
+        return ShowdownResult { pots, hands, payoffs: check_zero_sum(payoffs) }
+    }
+}
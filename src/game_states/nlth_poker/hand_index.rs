@@ -0,0 +1,54 @@
+use hand_isomorphism_rust::deck::Card;
+use hand_isomorphism_rust::hand_index::HandIndexer;
+use lazy_static::lazy_static;
+use smallvec::SmallVec;
+
+use crate::constants::{COMMUNITY_CARD_AMOUNT, PRIVATE_CARD_AMOUNT, ROUNDS};
+
+/*
+    Suit isomorphic hands share a canonical index, which runs from 0 up to get_hand_index_amount of the round.
+    Regrets and strategies can be stored in a dense array per round instead of a hash map.
+    Every round has its own indexer over the hole cards followed by the board of that round, in the order the cards were dealt:
+        preflop: 2 cards, flop: 2 + 3 cards, turn: 2 + 3 + 1 cards, river: 2 + 3 + 1 + 1 cards
+    The indexers of hand_isomorphism_rust (a port of hand_index.c by Kevin Waugh) take a while to build, so they are built once.
+*/
+
+const CARDS_PER_ROUND: [u8; ROUNDS] = [PRIVATE_CARD_AMOUNT as u8, 3, 1, 1];
+
+lazy_static! {
+    static ref HAND_INDEXERS: Vec<HandIndexer> = {
+        return (1..=ROUNDS).map(|rounds| HandIndexer::init(rounds as u32, CARDS_PER_ROUND[..rounds].to_vec())).collect()
+    };
+}
+
+fn get_card_amount(round: usize) -> usize {
+    return CARDS_PER_ROUND[..=round].iter().map(|&card_amount| card_amount as usize).sum()
+}
+
+// The amount of canonical hands in a round
+pub fn get_hand_index_amount(round: usize) -> u64 {
+    return HAND_INDEXERS[round].hand_indexer_size(round)
+}
+
+// `cards` are the hole cards followed by the board of the round
+pub fn get_hand_index(round: usize, cards: &[Card]) -> Result<u64, String> {
+    if round >= ROUNDS {
+        return Err(format!("Invalid round {}", round))
+    }
+    if cards.len() != get_card_amount(round) {
+        return Err(format!("Round {} needs {} cards, got {}", round, get_card_amount(round), cards.len()))
+    }
+    return Ok(HAND_INDEXERS[round].hand_index_last(cards))
+}
+
+// A hand with the given canonical index, the hole cards followed by the board of the round
+pub fn get_hand_from_index(round: usize, hand_index: u64) -> Result<SmallVec<[Card; PRIVATE_CARD_AMOUNT + COMMUNITY_CARD_AMOUNT]>, String> {
+    if round >= ROUNDS {
+        return Err(format!("Invalid round {}", round))
+    }
+    let mut cards = SmallVec::from_elem(0, get_card_amount(round));
+    if !HAND_INDEXERS[round].hand_unindex(round, hand_index, &mut cards) {
+        return Err(format!("Invalid hand index {} for round {}", hand_index, round))
+    }
+    return Ok(cards)
+}
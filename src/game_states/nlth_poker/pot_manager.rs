@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::constants::MAX_PLAYERS;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pot {
+    pub amount: u32,
+    // The players that can win the pot, in seat order
+    pub eligible_players: SmallVec<[usize; MAX_PLAYERS]>,
+}
+
+/*
+    The main pot and side pots of a hand, built from how many chips every player put in over the whole hand.
+    NLTHGameState keeps one and updates it after every action, there is no other record of the pots.
+    An all-in caps what that player can win: every all-in amount of a player that is still in the hand starts a new pot.
+    A pot can be won by the players that didn't fold and either matched the amount it is capped at or can still put in more chips.
+    Chips of folded players stay in the pots they were put into, chips that no remaining player can win go to the last pot.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PotManager {
+    pots: SmallVec<[Pot; MAX_PLAYERS]>,
+}
+
+impl PotManager {
+    pub fn from_contributions(contributions: &[u32], folded_players: &[bool], all_in_players: &[bool]) -> Self {
+        let mut pot_manager = PotManager::default();
+        pot_manager.update(contributions, folded_players, all_in_players);
+        return pot_manager
+    }
+
+    // Rebuilds the pots from the total contributions of every player so far
+    pub fn update(&mut self, contributions: &[u32], folded_players: &[bool], all_in_players: &[bool]) {
+        let player_amount = contributions.len();
+        let highest_contribution = contributions.iter().copied().max().unwrap_or(0);
+
+        let mut levels = (0..player_amount)
+            .filter(|&player_index| !folded_players[player_index] && all_in_players[player_index])
+            .map(|player_index| contributions[player_index])
+            .filter(|&contribution| contribution < highest_contribution)
+            .collect::<SmallVec<[u32; MAX_PLAYERS + 1]>>();
+        levels.push(highest_contribution);
+        levels.sort_unstable();
+        levels.dedup();
+
+        self.pots.clear();
+        let mut previous_level = 0;
+        for level in levels {
+            let amount = contributions.iter()
+                .map(|&contribution| contribution.min(level) - contribution.min(previous_level))
+                .sum::<u32>();
+            let eligible_players = (0..player_amount)
+                .filter(|&player_index| !folded_players[player_index])
+                .filter(|&player_index| contributions[player_index] >= level || !all_in_players[player_index])
+                .collect::<SmallVec<[usize; MAX_PLAYERS]>>();
+            previous_level = level;
+
+            if amount == 0 {
+                continue
+            }
+            match self.pots.last_mut() {
+                // Pots with the same players are one and the same, chips nobody can win go to the last pot
+                Some(last_pot) if last_pot.eligible_players == eligible_players || eligible_players.is_empty() => last_pot.amount += amount,
+                _ => self.pots.push(Pot { amount, eligible_players }),
+            }
+        }
+    }
+
+    // The main pot first, followed by the side pots
+    pub fn pots(&self) -> &[Pot] {
+        return &self.pots
+    }
+
+    pub fn get_total(&self) -> u32 {
+        return self.pots.iter().map(|pot| pot.amount).sum()
+    }
+}
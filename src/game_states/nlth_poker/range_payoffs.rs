@@ -0,0 +1,140 @@
+use crate::constants::{BOARD_CARD_AMOUNTS, COMMUNITY_CARD_AMOUNT, HOLE_CARD_COMBOS, NO_CARD_PLACEHOLDER};
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::showdown::split_pot;
+use super::game_state::NLTHGameState;
+use super::rank::{get_hole_card_combo, rank_hole_card_combos, BLOCKED_HAND_RANK};
+
+/*
+    Terminal payoffs of a whole range at once, for CFR that works on ranges instead of single hands.
+    Both take the range of the opponent as weights over the 1326 combos (indexed by get_combo_index) and return,
+    for every combo the player could hold, the payoff of holding it summed over the opponent's combos and weighted by them.
+    Combos that share a card with the board, or with the hand the player holds, can't be dealt and don't count.
+    Only heads-up states are supported, the chips come from the pots of the state.
+    A showdown needs the whole board. When the players went all-in before the river and the rest of the board isn't dealt yet,
+    the payoffs depend on the runout, see NLTHGameState::expected_payoffs.
+*/
+
+pub type RangeWeights = [f32; HOLE_CARD_COMBOS];
+
+// The payoffs of a state where both players went to showdown. Sorting the combos by rank makes it O(n log n) instead of comparing every pair of combos
+pub fn showdown_payoffs(game_state: &NLTHGameState, player_index: usize, opponent_range: &RangeWeights) -> Result<RangeWeights, String> {
+    let opponent_index = get_opponent_index(game_state, player_index)?;
+    if game_state.folded_players[..2].iter().any(|&folded| folded) {
+        return Err("A player folded, use fold_payoffs".to_string())
+    }
+    if game_state.community_cards.contains(&NO_CARD_PLACEHOLDER) {
+        return Err("The board is not complete, use expected_payoffs to go over the runouts".to_string())
+    }
+
+    // What the player ends up with when they win, lose or tie, from the pots they can win
+    let contributions = game_state.get_contributions();
+    let [mut win_payoff, mut lose_payoff, mut tie_payoff] = [-(contributions[player_index] as f32); 3];
+    for pot in game_state.get_pot_manager().pots() {
+        if !pot.eligible_players.contains(&player_index) {
+            continue
+        }
+        win_payoff += pot.amount as f32;
+        if !pot.eligible_players.contains(&opponent_index) {
+            lose_payoff += pot.amount as f32;
+            tie_payoff += pot.amount as f32;
+            continue
+        }
+        let split = split_pot(pot.amount, &[0, 1], game_state.get_odd_chip_order());
+        tie_payoff += split.iter().find(|&&(winner_index, _)| winner_index == player_index).unwrap().1 as f32;
+    }
+
+    let board: [u8; COMMUNITY_CARD_AMOUNT] = game_state.community_cards;
+    let hand_ranks = rank_hole_card_combos(&board);
+    let mut sorted_combos = (0..HOLE_CARD_COMBOS)
+        .filter(|&combo_index| hand_ranks[combo_index] != BLOCKED_HAND_RANK)
+        .collect::<Vec<usize>>();
+    sorted_combos.sort_unstable_by_key(|&combo_index| hand_ranks[combo_index]);
+
+    // From the weakest to the strongest, every combo beats the opponent's combos that came before it, minus the ones it shares a card with
+    let mut beaten_weights = [0.0f32; HOLE_CARD_COMBOS];
+    let mut beaten_weight = 0.0;
+    let mut beaten_card_weights = [0.0f32; 52];
+    for group in sorted_combos.chunk_by(|&a, &b| hand_ranks[a] == hand_ranks[b]) {
+        for &combo_index in group {
+            let [card_a, card_b] = get_hole_card_combo(combo_index);
+            beaten_weights[combo_index] = beaten_weight - beaten_card_weights[card_a as usize] - beaten_card_weights[card_b as usize];
+        }
+        for &combo_index in group {
+            let [card_a, card_b] = get_hole_card_combo(combo_index);
+            beaten_weight += opponent_range[combo_index];
+            beaten_card_weights[card_a as usize] += opponent_range[combo_index];
+            beaten_card_weights[card_b as usize] += opponent_range[combo_index];
+        }
+    }
+
+    // After going through every combo, the beaten weights are those of the whole range that can be dealt on this board
+    let (total_weight, card_weights) = (beaten_weight, beaten_card_weights);
+
+    // And the same the other way around for the combos that beat it, whatever is left of the range ties
+    let mut payoffs = [0.0; HOLE_CARD_COMBOS];
+    let mut beating_weight = 0.0;
+    let mut beating_card_weights = [0.0f32; 52];
+    for group in sorted_combos.chunk_by(|&a, &b| hand_ranks[a] == hand_ranks[b]).rev() {
+        for &combo_index in group {
+            let [card_a, card_b] = get_hole_card_combo(combo_index);
+            let beating_weights = beating_weight - beating_card_weights[card_a as usize] - beating_card_weights[card_b as usize];
+            // The opponent's combo with the same two cards is taken away twice, so it is added back once
+            let reachable_weight = total_weight - card_weights[card_a as usize] - card_weights[card_b as usize] + opponent_range[combo_index];
+            let tied_weight = reachable_weight - beaten_weights[combo_index] - beating_weights;
+            payoffs[combo_index] = win_payoff * beaten_weights[combo_index] + lose_payoff * beating_weights + tie_payoff * tied_weight;
+        }
+        for &combo_index in group {
+            let [card_a, card_b] = get_hole_card_combo(combo_index);
+            beating_weight += opponent_range[combo_index];
+            beating_card_weights[card_a as usize] += opponent_range[combo_index];
+            beating_card_weights[card_b as usize] += opponent_range[combo_index];
+        }
+    }
+
+    return Ok(payoffs)
+}
+
+// The payoffs of a state where a player folded. The payoff doesn't depend on the cards, only on how much of the opponent's range can still be dealt
+pub fn fold_payoffs(game_state: &NLTHGameState, player_index: usize, opponent_range: &RangeWeights) -> Result<RangeWeights, String> {
+    get_opponent_index(game_state, player_index)?;
+    if !game_state.folded_players[..2].iter().any(|&folded| folded) {
+        return Err("Nobody folded, use showdown_payoffs".to_string())
+    }
+    let payoff = game_state.get_payoffs()[player_index] as f32;
+
+    // Only the cards of the rounds that were reached are on the board, the others were never seen
+    let board = &game_state.community_cards[..BOARD_CARD_AMOUNTS[game_state.round]];
+    let is_blocked_by_board = |combo_index: usize| get_hole_card_combo(combo_index).iter().any(|card| board.contains(card));
+
+    let mut total_weight = 0.0;
+    let mut card_weights = [0.0f32; 52];
+    for combo_index in (0..HOLE_CARD_COMBOS).filter(|&combo_index| !is_blocked_by_board(combo_index)) {
+        let [card_a, card_b] = get_hole_card_combo(combo_index);
+        total_weight += opponent_range[combo_index];
+        card_weights[card_a as usize] += opponent_range[combo_index];
+        card_weights[card_b as usize] += opponent_range[combo_index];
+    }
+
+    let mut payoffs = [0.0; HOLE_CARD_COMBOS];
+    for combo_index in (0..HOLE_CARD_COMBOS).filter(|&combo_index| !is_blocked_by_board(combo_index)) {
+        let [card_a, card_b] = get_hole_card_combo(combo_index);
+        // The opponent's combo with the same two cards is taken away twice, so it is added back once
+        let reachable_weight = total_weight - card_weights[card_a as usize] - card_weights[card_b as usize] + opponent_range[combo_index];
+        payoffs[combo_index] = payoff * reachable_weight;
+    }
+
+    return Ok(payoffs)
+}
+
+fn get_opponent_index(game_state: &NLTHGameState, player_index: usize) -> Result<usize, String> {
+    if game_state.player_amount != 2 {
+        return Err(format!("Range payoffs need a heads-up state, this one has {} players", game_state.player_amount))
+    }
+    if player_index > 1 {
+        return Err(format!("Invalid player index {}", player_index))
+    }
+    if !game_state.is_terminal() {
+        return Err("The state is not terminal".to_string())
+    }
+    return Ok(1 - player_index)
+}
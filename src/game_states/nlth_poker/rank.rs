@@ -1,30 +1,190 @@
-use std::collections::HashMap;
-
-use hand_isomorphism_rust::deck::card_from_string;
-use holdem_hand_evaluator::Hand;
+use hand_isomorphism_rust::deck::{card_from_string, deck_get_rank};
+use holdem_hand_evaluator::{get_hand_category as evaluator_hand_category, Hand, HandCategory as EvaluatorHandCategory};
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{COMMUNITY_CARD_AMOUNT, HOLE_CARD_COMBOS};
 
 lazy_static! {
-    static ref CARD_ISOMORPHISM_TO_INDEX_LOOKUP: HashMap<u8, usize> = {
-        let mut m = HashMap::new();
+    // Flat lookup from the card of hand_isomorphism_rust to the card index of holdem_hand_evaluator
+    static ref CARD_ISOMORPHISM_TO_INDEX_LOOKUP: [usize; 52] = {
+        let mut m = [0; 52];
         let ranks = vec!["2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A"];
         let suits = vec!["c", "d", "h", "s",];
         for (i, rank) in ranks.iter().enumerate() {
             for (j, suit) in suits.iter().enumerate() {
                 let card_isomorphism_id = card_from_string(format!("{}{}", rank, suit));
                 let card_index = i * suits.len() + j;
-                m.insert(card_isomorphism_id, card_index);
+                m[card_isomorphism_id as usize] = card_index;
+            }
+        }
+        return m
+    };
+
+    // Every two card combination, ordered by the lower card and then the higher card
+    static ref HOLE_CARD_COMBO_LOOKUP: [[u8; 2]; HOLE_CARD_COMBOS] = {
+        let mut m = [[0; 2]; HOLE_CARD_COMBOS];
+        for card_a in 0..52u8 {
+            for card_b in (card_a + 1)..52u8 {
+                m[get_combo_index(card_a, card_b)] = [card_a, card_b];
             }
         }
         return m
     };
 }
 
-pub fn rank_hand(hand: Vec<u8>) -> u16 {
-    let hand_index = hand.into_iter()
-        .map(|card| CARD_ISOMORPHISM_TO_INDEX_LOOKUP[&card])
-        .collect::<Vec<usize>>();
-    let evaluator_hand = Hand::from_slice(&hand_index);
+// The rank of a combo that shares a card with the board. Seven cards always make a better hand than the worst five card hand, so no real hand has this rank
+pub const BLOCKED_HAND_RANK: u16 = 0;
+
+fn to_evaluator_hand(cards: &[u8]) -> Hand {
+    return cards.iter().fold(Hand::new(), |hand, &card| hand.add_card(CARD_ISOMORPHISM_TO_INDEX_LOOKUP[card as usize]))
+}
+
+// Ranks 5 to 7 cards, a higher rank is a better hand
+pub fn rank_hand(hand: &[u8]) -> u16 {
+    return to_evaluator_hand(hand).evaluate()
+}
+
+// The index of a two card combination in 0..HOLE_CARD_COMBOS, the order of the cards doesn't matter
+pub fn get_combo_index(card_a: u8, card_b: u8) -> usize {
+    let (low, high) = (card_a.min(card_b) as usize, card_a.max(card_b) as usize);
+    return low * (2 * 52 - low - 1) / 2 + (high - low - 1)
+}
+
+pub fn get_hole_card_combo(combo_index: usize) -> [u8; 2] {
+    return HOLE_CARD_COMBO_LOOKUP[combo_index]
+}
+
+// Ranks every two card combination on a complete board at once, indexed by get_combo_index. Combos that share a card with the board get BLOCKED_HAND_RANK
+pub fn rank_hole_card_combos(board: &[u8; COMMUNITY_CARD_AMOUNT]) -> [u16; HOLE_CARD_COMBOS] {
+    let board_hand = to_evaluator_hand(board);
+    let mut hand_ranks = [BLOCKED_HAND_RANK; HOLE_CARD_COMBOS];
+    for (combo_index, hand_rank) in hand_ranks.iter_mut().enumerate() {
+        let [card_a, card_b] = HOLE_CARD_COMBO_LOOKUP[combo_index];
+        let (evaluator_card_a, evaluator_card_b) = (CARD_ISOMORPHISM_TO_INDEX_LOOKUP[card_a as usize], CARD_ISOMORPHISM_TO_INDEX_LOOKUP[card_b as usize]);
+        if board_hand.contains(evaluator_card_a) || board_hand.contains(evaluator_card_b) {
+            continue
+        }
+        *hand_rank = board_hand.add_card(evaluator_card_a).add_card(evaluator_card_b).evaluate();
+    }
+    return hand_ranks
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+// The category of a value returned by rank_hand
+pub fn get_hand_category(hand_rank: u16) -> HandCategory {
+    match evaluator_hand_category(hand_rank) {
+        EvaluatorHandCategory::HighCard         => return HandCategory::HighCard,
+        EvaluatorHandCategory::OnePair          => return HandCategory::OnePair,
+        EvaluatorHandCategory::TwoPair          => return HandCategory::TwoPair,
+        EvaluatorHandCategory::ThreeOfAKind     => return HandCategory::ThreeOfAKind,
+        EvaluatorHandCategory::Straight         => return HandCategory::Straight,
+        EvaluatorHandCategory::Flush            => return HandCategory::Flush,
+        EvaluatorHandCategory::FullHouse        => return HandCategory::FullHouse,
+        EvaluatorHandCategory::FourOfAKind      => return HandCategory::FourOfAKind,
+        EvaluatorHandCategory::StraightFlush    => return HandCategory::StraightFlush,
+    }
+}
+
+const RANK_NAMES: [&str; 13] = ["Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen", "King", "Ace"];
+
+/*
+    A readable version of what rank_hand returns, for logs, UIs and features that depend on the made hand.
+        category: high card up to straight flush
+        best_cards: the five cards that make the hand. The cards that make the category come first, followed by the kickers,
+                    both from high to low. A five high straight starts with the five
+        description: for example "Two pair, Kings and Sevens, Ace kicker"
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandDescription {
+    pub category: HandCategory,
+    pub best_cards: [u8; 5],
+    pub description: String,
+}
+
+pub fn describe_hand(hand: &[u8]) -> Result<HandDescription, String> {
+    if hand.len() < 5 || hand.len() > 7 {
+        return Err(format!("A hand needs 5 to 7 cards to be described, got {}", hand.len()))
+    }
+    if let Some(&card) = hand.iter().find(|&&card| card >= 52) {
+        return Err(format!("Invalid card {}", card))
+    }
+    if hand.iter().enumerate().any(|(i, card)| hand[..i].contains(card)) {
+        return Err("A hand can't hold the same card twice".to_string())
+    }
+
+    // Out of the at most 21 ways to pick five cards, take the one that ranks highest
+    let mut best_cards = [0u8; 5];
+    let mut best_rank = 0;
+    for mask in 0u32..(1 << hand.len()) {
+        if mask.count_ones() != 5 {
+            continue
+        }
+        let mut cards = [0u8; 5];
+        for (card, i) in cards.iter_mut().zip((0..hand.len()).filter(|&i| mask & (1 << i) != 0)) {
+            *card = hand[i];
+        }
+        let hand_rank = rank_hand(&cards);
+        if hand_rank >= best_rank {
+            best_rank = hand_rank;
+            best_cards.copy_from_slice(&cards);
+        }
+    }
+    let category = get_hand_category(best_rank);
+
+    // Cards of the same rank are grouped, bigger groups first and higher ranks first within the same group size
+    let unsorted_cards = best_cards;
+    best_cards.sort_by_key(|&card| {
+        let rank = deck_get_rank(card);
+        let group_size = unsorted_cards.iter().filter(|&&other_card| deck_get_rank(other_card) == rank).count();
+        return std::cmp::Reverse((group_size, rank))
+    });
+    let is_wheel = matches!(category, HandCategory::Straight | HandCategory::StraightFlush)
+        && deck_get_rank(best_cards[0]) == 12 && deck_get_rank(best_cards[1]) == 3;
+    if is_wheel {
+        best_cards.rotate_left(1);
+    }
+
+    let ranks = best_cards.map(|card| deck_get_rank(card) as usize);
+    let description = match category {
+        HandCategory::HighCard          => format!("High card, {}, {}", RANK_NAMES[ranks[0]], describe_kickers(&ranks[1..])),
+        HandCategory::OnePair           => format!("Pair of {}, {}", plural_rank_name(ranks[0]), describe_kickers(&ranks[2..])),
+        HandCategory::TwoPair           => format!("Two pair, {} and {}, {}", plural_rank_name(ranks[0]), plural_rank_name(ranks[2]), describe_kickers(&ranks[4..])),
+        HandCategory::ThreeOfAKind      => format!("Three of a kind, {}, {}", plural_rank_name(ranks[0]), describe_kickers(&ranks[3..])),
+        HandCategory::Straight          => format!("Straight, {} high", RANK_NAMES[ranks[0]]),
+        HandCategory::Flush             => format!("Flush, {}", ranks.iter().map(|&rank| RANK_NAMES[rank]).collect::<Vec<&str>>().join("-")),
+        HandCategory::FullHouse         => format!("Full house, {} full of {}", plural_rank_name(ranks[0]), plural_rank_name(ranks[3])),
+        HandCategory::FourOfAKind       => format!("Four of a kind, {}, {}", plural_rank_name(ranks[0]), describe_kickers(&ranks[4..])),
+        HandCategory::StraightFlush if ranks[0] == 12 => "Royal flush".to_string(),
+        HandCategory::StraightFlush     => format!("Straight flush, {} high", RANK_NAMES[ranks[0]]),
+    };
+
+    return Ok(HandDescription { category, best_cards, description })
+}
+
+fn plural_rank_name(rank: usize) -> String {
+    if RANK_NAMES[rank] == "Six" {
+        return "Sixes".to_string()
+    }
+    return format!("{}s", RANK_NAMES[rank])
+}
 
-    return evaluator_hand.evaluate()
+fn describe_kickers(ranks: &[usize]) -> String {
+    let names = ranks.iter().map(|&rank| RANK_NAMES[rank]).collect::<Vec<&str>>().join("-");
+    if ranks.len() == 1 {
+        return format!("{} kicker", names)
+    }
+    return format!("{} kickers", names)
 }
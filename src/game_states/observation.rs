@@ -0,0 +1,29 @@
+/*
+    Fixed-size numeric encodings of what a player knows, as input for value and policy networks (Deep CFR, ReBeL, ...).
+
+    observation_tensor: the current situation from the point of view of a player (their own cards, the public cards, bets and stacks).
+    information_state_tensor: the observation followed by the action history, which together identify the information set.
+
+    The layout of each game is documented on its implementation. Sizes never depend on the state, so `tensor_shape` can be queried without one.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TensorShape {
+    pub information_state: usize,
+    pub observation: usize,
+}
+
+pub trait ObservationTensors {
+    fn tensor_shape() -> TensorShape;
+    fn information_state_tensor(&self, player_index: usize) -> Vec<f32>;
+    fn observation_tensor(&self, player_index: usize) -> Vec<f32>;
+}
+
+// Appends `size` values to the tensor, with a 1 at `index` if there is one
+pub(crate) fn push_one_hot(tensor: &mut Vec<f32>, size: usize, index: Option<usize>) {
+    let start = tensor.len();
+    tensor.resize(start + size, 0.0);
+    if let Some(index) = index {
+        tensor[start + index] = 1.0;
+    }
+}
@@ -0,0 +1,59 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/*
+    Versioned representation of a game state for persisting, sending between processes or attaching to bug reports.
+    The game state is wrapped in an envelope with a format version, so files written by older versions can be detected
+    (and migrated) instead of silently being read into the wrong fields.
+
+    Bump GAME_STATE_FORMAT_VERSION whenever a field of a game state is removed, renamed or changes meaning, or a field is added that older files can't do without.
+    A new field with #[serde(default)] doesn't need a bump as long as the default gives the behaviour from before the field existed,
+    older files then still load into the exact same game state (e.g. NLTHGameState's odd_chip_rule, players_acted and bet_rounding).
+*/
+pub const GAME_STATE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionedGameState<T> {
+    pub version: u32,
+    pub game_state: T,
+}
+
+impl<T: Serialize + DeserializeOwned> VersionedGameState<T> {
+    pub fn new(game_state: T) -> Self {
+        return VersionedGameState { version: GAME_STATE_FORMAT_VERSION, game_state }
+    }
+
+    pub fn into_game_state(self) -> Result<T, String> {
+        if self.version != GAME_STATE_FORMAT_VERSION {
+            return Err(format!("unsupported game state format version {}, expected {}", self.version, GAME_STATE_FORMAT_VERSION));
+        }
+        return Ok(self.game_state)
+    }
+}
+
+// Same layout as VersionedGameState, but borrows the game state so it doesn't have to be cloned for serializing
+#[derive(Serialize)]
+struct VersionedGameStateRef<'a, T> {
+    version: u32,
+    game_state: &'a T,
+}
+
+pub fn game_state_to_json<T: Serialize>(game_state: &T) -> String {
+    return serde_json::to_string(&VersionedGameStateRef { version: GAME_STATE_FORMAT_VERSION, game_state }).unwrap()
+}
+
+pub fn game_state_from_json<T: Serialize + DeserializeOwned>(json: &str) -> Result<T, String> {
+    // Read the version first, so an outdated file gives a version error rather than a missing field error
+    let version = serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|error| format!("invalid game state json: {}", error))?
+        .get("version")
+        .and_then(|version| version.as_u64())
+        .ok_or("game state json has no version")?;
+    if version != GAME_STATE_FORMAT_VERSION as u64 {
+        return Err(format!("unsupported game state format version {}, expected {}", version, GAME_STATE_FORMAT_VERSION));
+    }
+
+    return serde_json::from_str::<VersionedGameState<T>>(json)
+        .map_err(|error| format!("invalid game state json: {}", error))?
+        .into_game_state()
+}
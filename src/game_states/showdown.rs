@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::constants::MAX_PLAYERS;
+use crate::game_states::nlth_poker::rank::HandCategory;
+
+/*
+    How a finished hand was settled, the same for every variant so results can be shown and exported the same way.
+        pots: every pot with the players that could win it and what each winner received. Kuhn and Leduc poker have a single pot
+        hands: the rank and category of every player that went to showdown, empty when everyone else folded.
+               A higher hand_rank is a better hand, ranks can only be compared within a variant
+        payoffs: the same as get_payoffs
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShownHand {
+    pub player_index: usize,
+    pub hand_rank: u16,
+    pub category: HandCategory,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PotResult {
+    pub amount: u32,
+    pub eligible_players: SmallVec<[usize; MAX_PLAYERS]>,
+    // (player index, chips received), odd chips included
+    pub winners: SmallVec<[(usize, u32); MAX_PLAYERS]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShowdownResult {
+    pub pots: Vec<PotResult>,
+    pub hands: Vec<ShownHand>,
+    pub payoffs: [i32; MAX_PLAYERS],
+}
+
+impl ShowdownResult {
+    // The chips a player received over all pots
+    pub fn get_amount_won(&self, player_index: usize) -> u32 {
+        return self.pots.iter()
+            .flat_map(|pot| pot.winners.iter())
+            .filter(|&&(winner_index, _)| winner_index == player_index)
+            .map(|&(_, amount)| amount)
+            .sum()
+    }
+
+    pub fn get_hand(&self, player_index: usize) -> Option<&ShownHand> {
+        return self.hands.iter().find(|hand| hand.player_index == player_index)
+    }
+}
+
+pub trait Showdown {
+    // Only meaningful for terminal states
+    fn get_showdown_result(&self) -> ShowdownResult;
+}
+
+// Splits a pot evenly between the winners, the remaining chips go one by one to the winners that come first in `odd_chip_order`
+pub(crate) fn split_pot(amount: u32, winners: &[usize], odd_chip_order: impl Iterator<Item = usize>) -> SmallVec<[(usize, u32); MAX_PLAYERS]> {
+    let mut split = winners.iter().map(|&player_index| (player_index, amount / winners.len() as u32)).collect::<SmallVec<[(usize, u32); MAX_PLAYERS]>>();
+    let odd_chips = amount as usize % winners.len().max(1);
+    for player_index in odd_chip_order.filter(|player_index| winners.contains(player_index)).take(odd_chips) {
+        split.iter_mut().find(|(winner_index, _)| *winner_index == player_index).unwrap().1 += 1;
+    }
+    return split
+}
@@ -0,0 +1,317 @@
+use hand_isomorphism_rust::deck::{card_from_string, Card, RANK_TO_CHAR, SUIT_TO_CHAR};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT, ROUNDS};
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::nlth_poker::game_state::{format_cards, NLTHGameState};
+use crate::structs::{Action, ActionType, BetSizing};
+
+/*
+    Import and export of the PHH (Poker Hand History) format, a TOML file with the table setup and a list of action strings:
+        "d dh p1 AsKs"  deal hole cards (unknown cards are written as ??)
+        "d db JdQhTd"   deal board cards
+        "p1 f"          fold
+        "p1 cc"         check or call
+        "p1 cbr 300"    bet or raise to 300 chips in total for the street
+        "p1 sm AsKs"    show (or muck) at showdown
+
+    PHH orders players by position with the button last, so with 3+ players p1 is the small blind (player 0 in `NLTHGameState`).
+    In heads-up the button posts the small blind and acts last postflop, so p1 is the big blind (player 1) and p2 the small blind (player 0).
+    Like pokerkit we list the heads-up blinds as [small blind, big blind].
+*/
+
+const VARIANT_NO_LIMIT_TEXAS_HOLDEM: &str = "NT";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PHHRecord {
+    pub variant: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ante_trimming_status: Option<bool>,
+    pub antes: Vec<u32>,
+    pub blinds_or_straddles: Vec<u32>,
+    pub min_bet: u32,
+    pub starting_stacks: Vec<u32>,
+    pub actions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub players: Option<Vec<String>>,
+}
+
+impl PHHRecord {
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        return toml::from_str(text).map_err(|error| format!("invalid PHH file: {}", error))
+    }
+
+    pub fn to_toml(&self) -> String {
+        return toml::to_string(self).unwrap()
+    }
+}
+
+// Maps a player index of `NLTHGameState` onto the 1-based PHH player number
+fn phh_player_number(player_index: usize, player_amount: usize) -> usize {
+    if player_amount == 2 {
+        return 2 - player_index
+    }
+    return player_index + 1
+}
+
+fn player_index_of_phh_player(phh_player_number: usize, player_amount: usize) -> usize {
+    if player_amount == 2 {
+        return 2 - phh_player_number
+    }
+    return phh_player_number - 1
+}
+
+// Parses concatenated cards like "AsKs". Unknown cards ("??") become NO_CARD_PLACEHOLDER
+fn cards_from_phh_string(value: &str) -> Option<Vec<Card>> {
+    let chars = value.chars().collect::<Vec<char>>();
+    if chars.len() % 2 != 0 {
+        return None
+    }
+
+    return chars.chunks(2).map(|card_chars| {
+        let (rank, suit) = (card_chars[0].to_string(), card_chars[1].to_string());
+        if rank == "?" && suit == "?" {
+            return Some(NO_CARD_PLACEHOLDER)
+        }
+        if !RANK_TO_CHAR.iter().any(|rank_char| rank_char.to_string() == rank) ||
+            !SUIT_TO_CHAR.iter().any(|suit_char| suit_char.to_string() == suit)
+        {
+            return None
+        }
+        return Some(card_from_string(format!("{}{}", rank, suit)))
+    }).collect()
+}
+
+// Returns the cards dealt to the board at the start of a round
+fn board_cards_of_round(community_cards: &[Card; COMMUNITY_CARD_AMOUNT], round: usize) -> &[Card] {
+    match round {
+        1 => return &community_cards[0..3],
+        2 => return &community_cards[3..4],
+        3 => return &community_cards[4..5],
+        _ => return &[],
+    }
+}
+
+/*
+    Exports the hand played so far. The actions are replayed from the starting stacks, so bets keep the exact amounts `handle_action` gives them.
+    If the hand ended in a showdown, the rest of the board is dealt and the remaining players show their cards.
+    A state whose history doesn't replay to the chips that were actually bet can't be exported, e.g. one made with handle_translated_bet,
+    where the history holds the abstract bet instead of the real amount.
+*/
+pub fn export_phh(game_state: &NLTHGameState) -> Result<PHHRecord, String> {
+    let player_amount = game_state.player_amount;
+    let contributions = game_state.get_contributions();
+    let starting_stacks = (0..MAX_PLAYERS).map(|player_index| game_state.stacks[player_index] + contributions[player_index]).collect::<Vec<u32>>();
+    let phh_order = (1..=player_amount).map(|phh_player| player_index_of_phh_player(phh_player, player_amount)).collect::<Vec<usize>>();
+
+    let mut actions = vec![];
+    for &player_index in phh_order.iter() {
+        actions.push(format!(
+            "d dh p{} {}",
+            phh_player_number(player_index, player_amount),
+            format_cards(&game_state.private_hands[player_index])
+        ));
+    }
+
+    let mut replay_state = NLTHGameState::new_with_table(
+        player_amount, starting_stacks.clone().try_into().unwrap(), game_state.small_blind, game_state.big_blind, game_state.ante
+    );
+    // Pot fractions are rounded like in the hand itself
+    replay_state.bet_rounding = game_state.bet_rounding;
+    replay_state.odd_chip_rule = game_state.odd_chip_rule;
+    for round in 0..=game_state.round {
+        if round > 0 {
+            actions.push(format!("d db {}", format_cards(board_cards_of_round(&game_state.community_cards, round))));
+        }
+        for &action in game_state.history[round].iter() {
+            let player_index = replay_state.active_player_index;
+            let highest_bet = *replay_state.bets[round].iter().max().unwrap();
+            replay_state = replay_state.handle_action(action);
+            let round_bet = replay_state.bets[round][player_index];
+
+            let phh_action = match action.action_type {
+                ActionType::Fold => "f".to_owned(),
+                ActionType::Check | ActionType::Call => "cc".to_owned(),
+                // An all-in for less than the current bet is a call
+                ActionType::AllIn if round_bet <= highest_bet => "cc".to_owned(),
+                ActionType::AllIn | ActionType::Bet | ActionType::Raise => format!("cbr {}", round_bet),
+            };
+            actions.push(format!("p{} {}", phh_player_number(player_index, player_amount), phh_action));
+        }
+    }
+    if replay_state.bets != game_state.bets {
+        return Err("the history doesn't replay to the bets of the state, it holds bets that differ from the chips that were put in".to_owned());
+    }
+
+    let remaining_player_indices = phh_order.iter()
+        .filter(|&&player_index| !game_state.folded_players[player_index])
+        .copied()
+        .collect::<Vec<usize>>();
+    if game_state.is_terminal() && remaining_player_indices.len() > 1 {
+        for round in game_state.round + 1..ROUNDS {
+            actions.push(format!("d db {}", format_cards(board_cards_of_round(&game_state.community_cards, round))));
+        }
+        for player_index in remaining_player_indices {
+            actions.push(format!(
+                "p{} sm {}",
+                phh_player_number(player_index, player_amount),
+                format_cards(&game_state.private_hands[player_index])
+            ));
+        }
+    }
+
+    return Ok(PHHRecord {
+        variant: VARIANT_NO_LIMIT_TEXAS_HOLDEM.to_owned(),
+        ante_trimming_status: None,
+        antes: vec![game_state.ante; player_amount],
+        blinds_or_straddles: (0..player_amount).map(|phh_index| {
+            if phh_index == 0 {
+                return game_state.small_blind
+            } else if phh_index == 1 {
+                return game_state.big_blind
+            }
+            return 0
+        }).collect(),
+        min_bet: game_state.big_blind,
+        starting_stacks: phh_order.iter().map(|&player_index| starting_stacks[player_index]).collect(),
+        actions,
+        players: None,
+    })
+}
+
+/*
+    Replays a PHH record through `NLTHGameState`.
+    Returns the initial state after posting blinds and antes, followed by the state after each player action.
+    All dealt cards are known to every state, just like a state created with `new_empty(.., true, ..)`.
+*/
+pub fn import_phh(record: &PHHRecord) -> Result<Vec<NLTHGameState>, String> {
+    if record.variant != VARIANT_NO_LIMIT_TEXAS_HOLDEM {
+        return Err(format!("unsupported variant {}, only {} is supported", record.variant, VARIANT_NO_LIMIT_TEXAS_HOLDEM));
+    }
+    let player_amount = record.starting_stacks.len();
+    if !(2..=MAX_PLAYERS).contains(&player_amount) {
+        return Err(format!("{} players, only 2 to {} are supported", player_amount, MAX_PLAYERS));
+    }
+    if record.antes.len() != player_amount || record.blinds_or_straddles.len() != player_amount {
+        return Err("antes and blinds_or_straddles need a value for every player".to_owned());
+    }
+    if record.blinds_or_straddles[2..].iter().any(|&straddle| straddle != 0) {
+        return Err("straddles are not supported".to_owned());
+    }
+    let ante = record.antes[0];
+    if record.antes.iter().any(|&player_ante| player_ante != ante) {
+        return Err("antes that differ between players are not supported".to_owned());
+    }
+    // Heads-up blinds may be listed either way around
+    let small_blind = record.blinds_or_straddles[0].min(record.blinds_or_straddles[1]);
+    let big_blind = record.blinds_or_straddles[0].max(record.blinds_or_straddles[1]);
+
+    let mut stacks = [0; MAX_PLAYERS];
+    for (phh_index, &stack) in record.starting_stacks.iter().enumerate() {
+        stacks[player_index_of_phh_player(phh_index + 1, player_amount)] = stack;
+    }
+    for player_index in 0..player_amount {
+        let blind = if player_index == 0 { small_blind } else if player_index == 1 { big_blind } else { 0 };
+        if stacks[player_index] <= blind + ante {
+            return Err(format!("player {} cannot cover their blind and ante", phh_player_number(player_index, player_amount)));
+        }
+    }
+
+    let mut initial_state = NLTHGameState::new_with_table(player_amount, stacks, small_blind, big_blind, ante);
+    let mut board = vec![];
+    let mut player_actions = vec![];
+    for action_string in record.actions.iter() {
+        // Actions may have a trailing comment
+        let action_string = action_string.split('#').next().unwrap().trim();
+        let words = action_string.split_whitespace().collect::<Vec<&str>>();
+        let invalid_action = || format!("invalid action: {}", action_string);
+
+        if words.first() == Some(&"d") {
+            match words.get(1) {
+                Some(&"dh") => {
+                    let phh_player = words.get(2).and_then(|player| parse_phh_player(player, player_amount)).ok_or_else(invalid_action)?;
+                    let cards = words.get(3).and_then(|cards| cards_from_phh_string(cards)).ok_or_else(invalid_action)?;
+                    if cards.len() != PRIVATE_CARD_AMOUNT {
+                        return Err(invalid_action());
+                    }
+                    initial_state.private_hands[player_index_of_phh_player(phh_player, player_amount)] = [cards[0], cards[1]];
+                },
+                Some(&"db") => {
+                    board.extend(words.get(2).and_then(|cards| cards_from_phh_string(cards)).ok_or_else(invalid_action)?);
+                },
+                _ => return Err(invalid_action()),
+            }
+            continue;
+        }
+
+        let phh_player = words.first().and_then(|player| parse_phh_player(player, player_amount)).ok_or_else(invalid_action)?;
+        let player_index = player_index_of_phh_player(phh_player, player_amount);
+        match words.get(1) {
+            Some(&"sm") => {
+                // Cards shown at showdown, possibly unknown until now
+                if let Some(cards) = words.get(2).and_then(|cards| cards_from_phh_string(cards)) {
+                    if cards.len() == PRIVATE_CARD_AMOUNT && cards[0] != NO_CARD_PLACEHOLDER {
+                        initial_state.private_hands[player_index] = [cards[0], cards[1]];
+                    }
+                }
+            },
+            Some(&"f") => player_actions.push((player_index, ActionType::Fold, 0)),
+            Some(&"cc") => player_actions.push((player_index, ActionType::Call, 0)),
+            Some(&"cbr") => {
+                let amount = words.get(2).and_then(|amount| amount.parse::<u32>().ok()).ok_or_else(invalid_action)?;
+                player_actions.push((player_index, ActionType::Bet, amount));
+            },
+            _ => return Err(invalid_action()),
+        }
+    }
+    if board.len() > COMMUNITY_CARD_AMOUNT {
+        return Err(format!("the board has more than {} cards", COMMUNITY_CARD_AMOUNT));
+    }
+    for (card_index, &card) in board.iter().enumerate() {
+        initial_state.community_cards[card_index] = card;
+    }
+
+    let mut states = vec![initial_state];
+    for (player_index, action_type, amount) in player_actions {
+        let game_state = states.last().unwrap();
+        if game_state.is_terminal() {
+            return Err(format!("player {} acts after the hand is over", phh_player_number(player_index, player_amount)));
+        }
+        if game_state.active_player_index != player_index {
+            return Err(format!(
+                "player {} acts but it is player {}'s turn",
+                phh_player_number(player_index, player_amount), phh_player_number(game_state.active_player_index, player_amount)
+            ));
+        }
+
+        let stack = game_state.stacks[player_index];
+        let next_state = match action_type {
+            ActionType::Call if game_state.get_call_amount() >= stack => {
+                game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction })
+            },
+            ActionType::Bet => {
+                let extra_bets = amount.checked_sub(game_state.bets[game_state.round][player_index])
+                    .ok_or(format!("player {} raises to less than their current bet", phh_player_number(player_index, player_amount)))?;
+                if extra_bets > stack {
+                    return Err(format!("player {} bets more than their stack", phh_player_number(player_index, player_amount)));
+                } else if extra_bets == stack {
+                    game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction })
+                } else {
+                    game_state.handle_bet_amount(extra_bets)
+                }
+            },
+            _ => game_state.handle_action(Action { action_type, raise_amount: 0, sizing: BetSizing::PotFraction }),
+        };
+        states.push(next_state);
+    }
+
+    return Ok(states)
+}
+
+fn parse_phh_player(value: &str, player_amount: usize) -> Option<usize> {
+    let phh_player = value.strip_prefix('p')?.parse::<usize>().ok()?;
+    if phh_player == 0 || phh_player > player_amount {
+        return None
+    }
+    return Some(phh_player)
+}
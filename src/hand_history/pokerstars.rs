@@ -0,0 +1,490 @@
+use hand_isomorphism_rust::deck::{card_from_string, Card, RANK_TO_CHAR, SUIT_TO_CHAR};
+
+use crate::constants::{COMMUNITY_CARD_AMOUNT, MAX_PLAYERS, NO_CARD_PLACEHOLDER, PRIVATE_CARD_AMOUNT};
+use crate::game_states::base_game_state::GameState;
+use crate::game_states::nlth_poker::game_state::NLTHGameState;
+use crate::structs::{Action, ActionType, BetSizing};
+
+/*
+    Parser for PokerStars text hand histories (No Limit Hold'em only).
+    Every hand is replayed through `NLTHGameState` and the result is compared with what the log says happened.
+
+    Seats are mapped onto player indices the way `NLTHGameState` expects them: the small blind becomes player 0,
+    the big blind player 1 and the remaining players follow clockwise, which puts the button last.
+    Amounts in cash games are converted to cents so everything fits in whole chips.
+*/
+
+#[derive(Clone, Debug)]
+pub struct HandHistoryPlayer {
+    pub name: String,
+    pub seat: usize,
+    pub starting_stack: u32,
+    pub hole_cards: Option<[Card; PRIVATE_CARD_AMOUNT]>,
+    // Amount collected from all pots according to the log
+    pub collected: u32,
+    // Uncalled bets that were returned to the player
+    pub returned: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HandHistoryMismatch {
+    // The log wagered a different amount than the game state would for this action
+    Wager { action_index: usize, player_index: usize, logged: u32, computed: u32 },
+    // The log has no more actions but the game state is not terminal
+    NotTerminal,
+    // The total pot (excluding returned uncalled bets) differs
+    TotalPot { logged: u32, computed: u32 },
+    // The net winnings differ. Note that raked hands will always differ by the rake
+    Payoff { player_index: usize, logged: i32, computed: i32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct PokerStarsHand {
+    pub hand_id: String,
+    // Indexed by player index, not by seat
+    pub players: Vec<HandHistoryPlayer>,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
+    pub board: Vec<Card>,
+    pub total_pot: u32,
+    pub side_pots: Vec<u32>,
+    pub rake: u32,
+    // The initial state after posting blinds and antes, followed by the state after each action
+    pub states: Vec<NLTHGameState>,
+    // False when the payoffs could not be checked, e.g. because the cards of a player at showdown are unknown
+    pub payoffs_verified: bool,
+    pub mismatches: Vec<HandHistoryMismatch>,
+}
+
+impl PokerStarsHand {
+    pub fn final_state(&self) -> &NLTHGameState {
+        return self.states.last().unwrap()
+    }
+}
+
+// Splits a file with multiple hand histories and parses each of them. Hands that can't be parsed are returned as errors so one bad hand doesn't stop a whole corpus
+pub fn parse_hand_histories(text: &str) -> Vec<Result<PokerStarsHand, String>> {
+    let mut hands: Vec<Vec<&str>> = vec![];
+    for line in text.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.starts_with("PokerStars ") && line.contains('#') {
+            hands.push(vec![]);
+        }
+        if let Some(hand_lines) = hands.last_mut() {
+            if !line.is_empty() {
+                hand_lines.push(line);
+            }
+        }
+    }
+
+    return hands.iter().map(|hand_lines| parse_hand_lines(hand_lines)).collect();
+}
+
+pub fn parse_hand_history(text: &str) -> Result<PokerStarsHand, String> {
+    let lines = text.lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>();
+
+    return parse_hand_lines(&lines);
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Seats,
+    Actions,
+    Summary,
+}
+
+struct SeatInfo {
+    seat: usize,
+    name: String,
+    stack: u32,
+}
+
+fn parse_hand_lines(lines: &[&str]) -> Result<PokerStarsHand, String> {
+    let header = *lines.first().ok_or("empty hand history")?;
+    if !header.starts_with("PokerStars ") {
+        return Err(format!("not a PokerStars hand history: {}", header));
+    }
+    let hand_id = header.split('#').nth(1)
+        .and_then(|rest| rest.split(':').next())
+        .ok_or(format!("could not read the hand id: {}", header))?
+        .trim().to_owned();
+    if !header.contains("Hold'em No Limit") {
+        return Err(format!("hand {}: only No Limit Hold'em is supported", hand_id));
+    }
+    // Cash games have blinds like ($0.50/$1.00 USD), whose amounts we convert to cents. Tournament buy-ins have a currency too, but their blinds don't
+    let in_cents = header.split("Hold'em No Limit").nth(1)
+        .and_then(|rest| rest.split('(').nth(1))
+        .is_some_and(|blinds| blinds.starts_with(['$', '€', '£']));
+
+    let mut section = Section::Seats;
+    let mut seats: Vec<SeatInfo> = vec![];
+    let mut small_blind: Option<(String, u32)> = None;
+    let mut big_blind: Option<(String, u32)> = None;
+    let mut antes: Vec<(String, u32)> = vec![];
+    let mut hole_cards: Vec<(String, [Card; PRIVATE_CARD_AMOUNT])> = vec![];
+    let mut action_lines: Vec<(String, &str)> = vec![];
+    let mut collected: Vec<(String, u32)> = vec![];
+    let mut returned: Vec<(String, u32)> = vec![];
+    let mut board: Vec<Card> = vec![];
+    let mut total_pot = 0;
+    let mut side_pots = vec![];
+    let mut rake = 0;
+
+    for &line in lines[1..].iter() {
+        if line.starts_with("*** SUMMARY ***") {
+            section = Section::Summary;
+            continue;
+        }
+        if line.starts_with("*** ") {
+            if section == Section::Seats {
+                section = Section::Actions;
+            }
+            if line.contains("FIRST") || line.contains("SECOND") {
+                return Err(format!("hand {}: boards that are run multiple times are not supported", hand_id));
+            }
+            continue;
+        }
+
+        if section == Section::Seats && line.starts_with("Seat ") && line.contains(" in chips") {
+            if line.ends_with("is sitting out") || line.ends_with("out of hand") {
+                continue;
+            }
+            seats.push(parse_seat_line(line, in_cents).ok_or(format!("hand {}: could not read seat line: {}", hand_id, line))?);
+            continue;
+        }
+
+        if section == Section::Summary {
+            if line.starts_with("Total pot ") {
+                let (pot, pots, summary_rake) = parse_total_pot_line(line, in_cents).ok_or(format!("hand {}: could not read total pot: {}", hand_id, line))?;
+                total_pot = pot;
+                side_pots = pots;
+                rake = summary_rake;
+            } else if line.starts_with("Board [") {
+                board = parse_cards(line).ok_or(format!("hand {}: could not read board: {}", hand_id, line))?;
+            } else if line.starts_with("Seat ") && (line.contains(" showed [") || line.contains(" mucked [")) {
+                if let (Some(name), Some(cards)) = (summary_seat_name(line, &seats), parse_cards(line)) {
+                    if cards.len() == PRIVATE_CARD_AMOUNT {
+                        hole_cards.push((name, [cards[0], cards[1]]));
+                    }
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let (Some(name), Some(cards)) = (seats.iter().find(|seat| rest.starts_with(&format!("{} [", seat.name))), parse_cards(line)) {
+                if cards.len() == PRIVATE_CARD_AMOUNT {
+                    hole_cards.push((name.name.clone(), [cards[0], cards[1]]));
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let amount = rest.split(')').next().and_then(|amount| parse_amount(amount, in_cents));
+            let name = rest.split(" returned to ").nth(1);
+            if let (Some(amount), Some(name)) = (amount, name) {
+                returned.push((name.to_owned(), amount));
+                continue;
+            }
+            return Err(format!("hand {}: could not read uncalled bet: {}", hand_id, line));
+        }
+
+        // Players with longer names go first, so a player named "Bob" can't match a line from "Bob Jr"
+        let mut names = seats.iter().map(|seat| seat.name.as_str()).collect::<Vec<&str>>();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+
+        if let Some(&name) = names.iter().find(|name| line.starts_with(&format!("{} collected ", name))) {
+            let amount = line[name.len() + " collected ".len()..].split(' ').next().and_then(|amount| parse_amount(amount, in_cents));
+            collected.push((name.to_owned(), amount.ok_or(format!("hand {}: could not read collected amount: {}", hand_id, line))?));
+            continue;
+        }
+
+        if let Some(&name) = names.iter().find(|name| line.starts_with(&format!("{}: ", name))) {
+            let action = &line[name.len() + 2..];
+            if let Some(amount) = action.strip_prefix("posts small blind ") {
+                small_blind = Some((name.to_owned(), parse_amount(amount, in_cents).ok_or(format!("hand {}: could not read small blind: {}", hand_id, line))?));
+            } else if let Some(amount) = action.strip_prefix("posts big blind ") {
+                big_blind = Some((name.to_owned(), parse_amount(amount, in_cents).ok_or(format!("hand {}: could not read big blind: {}", hand_id, line))?));
+            } else if let Some(amount) = action.strip_prefix("posts the ante ") {
+                antes.push((name.to_owned(), parse_amount(amount, in_cents).ok_or(format!("hand {}: could not read ante: {}", hand_id, line))?));
+            } else if action.starts_with("posts ") {
+                return Err(format!("hand {}: unsupported post: {}", hand_id, line));
+            } else if action.starts_with("shows [") || action.starts_with("mucks [") {
+                if let Some(cards) = parse_cards(action) {
+                    if cards.len() == PRIVATE_CARD_AMOUNT {
+                        hole_cards.push((name.to_owned(), [cards[0], cards[1]]));
+                    }
+                }
+            } else if ["folds", "checks", "calls ", "bets ", "raises "].iter().any(|verb| action.starts_with(verb)) {
+                action_lines.push((name.to_owned(), action));
+            }
+            // Anything else (chat, timeouts, "doesn't show hand", ...) has no effect on the game
+        }
+    }
+
+    /*
+        Map seats onto player indices
+    */
+    let player_amount = seats.len();
+    if !(2..=MAX_PLAYERS).contains(&player_amount) {
+        return Err(format!("hand {}: {} players are dealt in, only 2 to {} are supported", hand_id, player_amount, MAX_PLAYERS));
+    }
+    let (small_blind_name, small_blind_amount) = small_blind.ok_or(format!("hand {}: hands without a small blind are not supported", hand_id))?;
+    let (big_blind_name, big_blind_amount) = big_blind.ok_or(format!("hand {}: hands without a big blind are not supported", hand_id))?;
+    seats.sort_by_key(|seat| seat.seat);
+    let small_blind_seat_index = seats.iter().position(|seat| seat.name == small_blind_name)
+        .ok_or(format!("hand {}: the small blind is posted by a player without a seat", hand_id))?;
+    seats.rotate_left(small_blind_seat_index);
+    if seats[1].name != big_blind_name {
+        return Err(format!("hand {}: the big blind is not posted by the player after the small blind", hand_id));
+    }
+    let player_index_of = |name: &str| seats.iter().position(|seat| seat.name == name);
+
+    let ante = antes.first().map(|(_, amount)| *amount).unwrap_or(0);
+    if antes.iter().any(|(_, amount)| *amount != ante) || (ante > 0 && antes.len() != player_amount) {
+        return Err(format!("hand {}: antes that differ between players are not supported", hand_id));
+    }
+
+    let mut stacks = [0; MAX_PLAYERS];
+    for (player_index, seat) in seats.iter().enumerate() {
+        let blind = if player_index == 0 { small_blind_amount } else if player_index == 1 { big_blind_amount } else { 0 };
+        // Posting a blind or ante all-in is not something the game state can represent
+        if seat.stack <= blind + ante {
+            return Err(format!("hand {}: {} cannot cover their blind and ante", hand_id, seat.name));
+        }
+        stacks[player_index] = seat.stack;
+    }
+
+    let mut players = seats.iter().map(|seat| HandHistoryPlayer {
+        name: seat.name.clone(),
+        seat: seat.seat,
+        starting_stack: seat.stack,
+        hole_cards: None,
+        collected: 0,
+        returned: 0,
+    }).collect::<Vec<HandHistoryPlayer>>();
+    for (name, cards) in hole_cards {
+        if let Some(player_index) = player_index_of(&name) {
+            players[player_index].hole_cards = Some(cards);
+        }
+    }
+    for (name, amount) in collected {
+        let player_index = player_index_of(&name).ok_or(format!("hand {}: unknown player {} collected a pot", hand_id, name))?;
+        players[player_index].collected += amount;
+    }
+    for (name, amount) in returned {
+        let player_index = player_index_of(&name).ok_or(format!("hand {}: uncalled bet returned to unknown player {}", hand_id, name))?;
+        players[player_index].returned += amount;
+    }
+
+    /*
+        Replay the hand
+    */
+    let mut game_state = NLTHGameState::new_with_table(player_amount, stacks, small_blind_amount, big_blind_amount, ante);
+    for (player_index, player) in players.iter().enumerate() {
+        if let Some(cards) = player.hole_cards {
+            game_state.private_hands[player_index] = cards;
+        }
+    }
+    if board.len() > COMMUNITY_CARD_AMOUNT {
+        return Err(format!("hand {}: the board has more than {} cards", hand_id, COMMUNITY_CARD_AMOUNT));
+    }
+    for (card_index, &card) in board.iter().enumerate() {
+        game_state.community_cards[card_index] = card;
+    }
+
+    let mut states = vec![game_state];
+    let mut mismatches = vec![];
+    for (action_index, (name, action)) in action_lines.iter().enumerate() {
+        let game_state = states.last().unwrap();
+        let player_index = player_index_of(name).unwrap();
+        if game_state.is_terminal() {
+            return Err(format!("hand {}: {} acts after the hand is over: {}", hand_id, name, action));
+        }
+        if game_state.active_player_index != player_index {
+            return Err(format!(
+                "hand {}: {} acts but it is {}'s turn: {}",
+                hand_id, name, players[game_state.active_player_index].name, action
+            ));
+        }
+
+        let is_all_in = action.ends_with("and is all-in");
+        let stack = game_state.stacks[player_index];
+        let current_bets = game_state.bets[game_state.round][player_index];
+        let call_amount = game_state.get_call_amount();
+
+        let next_state;
+        if *action == "folds" {
+            next_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        } else if *action == "checks" {
+            if call_amount != 0 {
+                mismatches.push(HandHistoryMismatch::Wager { action_index, player_index, logged: 0, computed: call_amount });
+            }
+            next_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        } else {
+            let words = action.split(' ').collect::<Vec<&str>>();
+            // "calls 100", "bets 100" or "raises 100 to 300"
+            let amount = match words[0] {
+                "raises" => words.get(3).and_then(|amount| parse_amount(amount, in_cents)),
+                _ => words.get(1).and_then(|amount| parse_amount(amount, in_cents)),
+            }.ok_or(format!("hand {}: could not read amount: {}", hand_id, action))?;
+            let logged_extra_bets = match words[0] {
+                "raises" => amount.checked_sub(current_bets).ok_or(format!("hand {}: raise to less than the current bet: {}", hand_id, action))?,
+                _ => amount,
+            };
+
+            if is_all_in {
+                if logged_extra_bets != stack {
+                    mismatches.push(HandHistoryMismatch::Wager { action_index, player_index, logged: logged_extra_bets, computed: stack });
+                }
+                next_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+            } else if words[0] == "calls" {
+                if logged_extra_bets != call_amount {
+                    mismatches.push(HandHistoryMismatch::Wager { action_index, player_index, logged: logged_extra_bets, computed: call_amount });
+                }
+                if call_amount >= stack {
+                    return Err(format!("hand {}: {} cannot afford to call without going all-in: {}", hand_id, name, action));
+                }
+                next_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+            } else {
+                if logged_extra_bets >= stack {
+                    return Err(format!("hand {}: {} bets their whole stack without going all-in: {}", hand_id, name, action));
+                }
+                next_state = game_state.handle_bet_amount(logged_extra_bets);
+            }
+        }
+        states.push(next_state);
+    }
+
+    /*
+        Compare the outcome
+    */
+    let final_state = states.last().unwrap();
+    let mut payoffs_verified = false;
+    if !final_state.is_terminal() {
+        mismatches.push(HandHistoryMismatch::NotTerminal);
+    } else {
+        let total_returned = players.iter().map(|player| player.returned).sum::<u32>();
+        let computed_total_pot = final_state.get_total_pot() - total_returned;
+        if computed_total_pot != total_pot {
+            mismatches.push(HandHistoryMismatch::TotalPot { logged: total_pot, computed: computed_total_pot });
+        }
+
+        // At a showdown we need the cards of every remaining player and a full board to rank the hands
+        let remaining_player_indices = (0..player_amount).filter(|&player_index| !final_state.folded_players[player_index]).collect::<Vec<usize>>();
+        let can_rank_hands = remaining_player_indices.iter().all(|&player_index| players[player_index].hole_cards.is_some()) &&
+            board.len() == COMMUNITY_CARD_AMOUNT;
+        if remaining_player_indices.len() == 1 || can_rank_hands {
+            payoffs_verified = true;
+            let payoffs = final_state.get_payoffs();
+            for (player_index, player) in players.iter().enumerate() {
+                let invested = (player.starting_stack - final_state.stacks[player_index]) as i32;
+                let logged = player.collected as i32 + player.returned as i32 - invested;
+                if logged != payoffs[player_index] {
+                    mismatches.push(HandHistoryMismatch::Payoff { player_index, logged, computed: payoffs[player_index] });
+                }
+            }
+        }
+    }
+
+    return Ok(PokerStarsHand {
+        hand_id,
+        players,
+        small_blind: small_blind_amount,
+        big_blind: big_blind_amount,
+        ante,
+        board,
+        total_pot,
+        side_pots,
+        rake,
+        states,
+        payoffs_verified,
+        mismatches,
+    })
+}
+
+// "Seat 3: Some Name (1500 in chips)" or "Seat 3: Some Name ($15.20 in chips) is sitting out"
+fn parse_seat_line(line: &str, in_cents: bool) -> Option<SeatInfo> {
+    let (seat_part, rest) = line.strip_prefix("Seat ")?.split_once(": ")?;
+    let seat = seat_part.parse::<usize>().ok()?;
+    let stack_start = rest.rfind(" (")?;
+    let name = rest[..stack_start].to_owned();
+    let stack_string = rest[stack_start + 2..].split(' ').next()?;
+
+    return Some(SeatInfo { seat, name, stack: parse_amount(stack_string, in_cents)? })
+}
+
+// "Seat 3: Some Name (big blind) showed [Ah Kd] and won (200)"
+fn summary_seat_name(line: &str, seats: &[SeatInfo]) -> Option<String> {
+    let rest = line.strip_prefix("Seat ")?.split_once(": ")?.1;
+    return seats.iter()
+        .filter(|seat| rest.starts_with(&format!("{} ", seat.name)))
+        .max_by_key(|seat| seat.name.len())
+        .map(|seat| seat.name.clone())
+}
+
+// "Total pot 3000 Main pot 2000. Side pot-1 1000. | Rake 0"
+fn parse_total_pot_line(line: &str, in_cents: bool) -> Option<(u32, Vec<u32>, u32)> {
+    let words = line.split_whitespace().collect::<Vec<&str>>();
+    let mut total_pot = None;
+    let mut side_pots = vec![];
+    let mut rake = 0;
+    for (word_index, &word) in words.iter().enumerate() {
+        let next = words.get(word_index + 1).copied().unwrap_or("");
+        let amount = words.get(word_index + 2).and_then(|amount| parse_amount(amount, in_cents));
+        if word == "Total" && next == "pot" {
+            total_pot = amount;
+        } else if word == "Side" && next.starts_with("pot") {
+            side_pots.push(amount?);
+        } else if word == "Rake" {
+            rake = parse_amount(next, in_cents)?;
+        }
+    }
+
+    return Some((total_pot?, side_pots, rake))
+}
+
+// Parses an amount like "1,500", "300.", "$0.50" or "€2" into chips (or cents)
+fn parse_amount(value: &str, in_cents: bool) -> Option<u32> {
+    let value = value.trim_start_matches(['$', '€', '£', '(']).trim_end_matches([')', ',']).replace(',', "");
+    let value = value.strip_suffix('.').unwrap_or(&value);
+    if !in_cents {
+        return value.parse::<u32>().ok()
+    }
+
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    if fraction.len() > 2 {
+        return None
+    }
+    let fraction = format!("{:0<2}", fraction).parse::<u32>().ok()?;
+    return whole.parse::<u32>().ok()?.checked_mul(100)?.checked_add(fraction)
+}
+
+// Parses the cards between the last pair of brackets in a line, e.g. "[Jd Qh Td] [5s]" gives the 5s
+fn parse_cards(line: &str) -> Option<Vec<Card>> {
+    let start = line.rfind('[')?;
+    let end = start + line[start..].find(']')?;
+    return line[start + 1..end].split_whitespace().map(parse_card).collect()
+}
+
+fn parse_card(value: &str) -> Option<Card> {
+    let mut chars = value.chars();
+    let (rank, suit) = (chars.next()?.to_string(), chars.next()?.to_string());
+    if chars.next().is_some() ||
+        !RANK_TO_CHAR.iter().any(|rank_char| rank_char.to_string() == rank) ||
+        !SUIT_TO_CHAR.iter().any(|suit_char| suit_char.to_string() == suit)
+    {
+        return None
+    }
+    let card = card_from_string(value.to_owned());
+    if card == NO_CARD_PLACEHOLDER {
+        return None
+    }
+
+    return Some(card)
+}
@@ -1,5 +1,11 @@
 pub mod structs;
 pub mod constants;
+pub mod env;
+pub mod action_abstraction;
+pub mod action_translation;
+pub mod card_abstraction;
+pub mod equity;
+pub mod range;
 pub mod game_states {
     pub mod kuhn_poker {
         pub mod game_state;
@@ -10,6 +16,17 @@ pub mod game_states {
     pub mod nlth_poker {
         pub mod game_state;
         pub mod rank;
+        pub mod hand_index;
+        pub mod pot_manager;
+        pub mod range_payoffs;
     }
     pub mod base_game_state;
+    pub mod serialization;
+    pub mod encoding;
+    pub mod observation;
+    pub mod showdown;
+}
+pub mod hand_history {
+    pub mod pokerstars;
+    pub mod phh;
 }
\ No newline at end of file
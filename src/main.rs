@@ -1,5 +1,11 @@
 pub mod structs;
 pub mod constants;
+pub mod env;
+pub mod action_abstraction;
+pub mod action_translation;
+pub mod card_abstraction;
+pub mod equity;
+pub mod range;
 pub mod game_states {
     pub mod kuhn_poker {
         pub mod game_state;
@@ -10,13 +16,37 @@ pub mod game_states {
     pub mod nlth_poker {
         pub mod game_state;
         pub mod rank;
+        pub mod hand_index;
+        pub mod pot_manager;
+        pub mod range_payoffs;
     }
     pub mod base_game_state;
+    pub mod serialization;
+    pub mod encoding;
+    pub mod observation;
+    pub mod showdown;
+}
+pub mod hand_history {
+    pub mod pokerstars;
+    pub mod phh;
 }
 mod tests {
     mod nlth_headsup;
     mod nlth_multiplayer;
     mod action_abstraction;
+    mod hand_history;
+    mod serialization;
+    mod encoding;
+    mod display;
+    mod observation;
+    mod env;
+    mod action_translation;
+    mod equity;
+    mod card_abstraction;
+    mod payoffs;
+    mod rank;
+    mod hand_index;
+    mod range;
 }
 
 use dotenv::dotenv;
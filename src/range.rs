@@ -0,0 +1,221 @@
+use hand_isomorphism_rust::deck::{card_from_string, deck_get_rank, deck_get_suit, deck_make_card, Card, RANK_TO_CHAR, SUIT_TO_CHAR};
+
+use crate::constants::{HOLE_CARD_COMBOS, PRIVATE_CARD_AMOUNT};
+use crate::game_states::nlth_poker::range_payoffs::RangeWeights;
+use crate::game_states::nlth_poker::rank::{get_combo_index, get_hole_card_combo};
+
+/*
+    A range is a weight for every one of the 1326 hole card combos (indexed by get_combo_index), parsed from the usual notation.
+    Parts are separated by commas, a later part overrides the weight of an earlier one:
+        pairs: "77", "77+" (77 up to AA), "22-55"
+        two different ranks: "AKs" (suited), "AKo" (offsuit), "AK" (both), "A2s+" (A2s up to AKs), "A2s-A5s"
+        single combos: "AhKd"
+        weights: any part can end in a weight, "AKs:0.5". Without one the weight is 1
+    The 169 preflop classes are the cells of the usual 13x13 grid, counted row by row from the top left:
+    aces come first, pairs are on the diagonal, suited hands above it and offsuit hands below it.
+*/
+
+pub const PREFLOP_CLASSES: usize = 169;
+const RANK_AMOUNT: u8 = 13;
+const GRID_CELL_WIDTH: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+// The preflop class of a pair of hole cards, 0 (AA) to 168 (22)
+pub fn get_preflop_class(hole_cards: [Card; PRIVATE_CARD_AMOUNT]) -> usize {
+    let high_rank = deck_get_rank(hole_cards[0]).max(deck_get_rank(hole_cards[1]));
+    let low_rank = deck_get_rank(hole_cards[0]).min(deck_get_rank(hole_cards[1]));
+    let (high_position, low_position) = ((RANK_AMOUNT - 1 - high_rank) as usize, (RANK_AMOUNT - 1 - low_rank) as usize);
+    if deck_get_suit(hole_cards[0]) == deck_get_suit(hole_cards[1]) {
+        return high_position * RANK_AMOUNT as usize + low_position
+    }
+    return low_position * RANK_AMOUNT as usize + high_position
+}
+
+// "AA", "AKs" or "AKo"
+pub fn get_preflop_class_name(preflop_class: usize) -> String {
+    let (row, column) = (preflop_class / RANK_AMOUNT as usize, preflop_class % RANK_AMOUNT as usize);
+    let rank_char = |position: usize| RANK_TO_CHAR[RANK_AMOUNT as usize - 1 - position];
+    if row == column {
+        return format!("{}{}", rank_char(row), rank_char(row))
+    } else if row < column {
+        return format!("{}{}s", rank_char(row), rank_char(column))
+    }
+    return format!("{}{}o", rank_char(column), rank_char(row))
+}
+
+fn parse_rank(rank_char: char) -> Result<u8, String> {
+    return RANK_TO_CHAR.iter()
+        .position(|&other_rank_char| other_rank_char == rank_char.to_ascii_uppercase())
+        .map(|rank| rank as u8)
+        .ok_or(format!("invalid rank \"{}\"", rank_char))
+}
+
+// A card like "Ah", checked before card_from_string gets it
+fn parse_card(card: &str) -> Result<Card, String> {
+    let chars = card.chars().collect::<Vec<char>>();
+    if chars.len() != 2 || !SUIT_TO_CHAR.contains(&chars[1]) {
+        return Err(format!("invalid card \"{}\"", card))
+    }
+    let rank = parse_rank(chars[0])?;
+    return Ok(card_from_string(format!("{}{}", RANK_TO_CHAR[rank as usize], chars[1])))
+}
+
+// A class like "AKs", "AK" or "77" as (high rank, low rank, suitedness)
+fn parse_class(class: &str) -> Result<(u8, u8, Suitedness), String> {
+    let chars = class.chars().collect::<Vec<char>>();
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err(format!("invalid hand class \"{}\"", class))
+    }
+    let (first_rank, second_rank) = (parse_rank(chars[0])?, parse_rank(chars[1])?);
+    let suitedness = match chars.get(2) {
+        None => Suitedness::Any,
+        Some('s') if first_rank != second_rank => Suitedness::Suited,
+        Some('o') if first_rank != second_rank => Suitedness::Offsuit,
+        Some(_) => return Err(format!("invalid hand class \"{}\"", class)),
+    };
+    return Ok((first_rank.max(second_rank), first_rank.min(second_rank), suitedness))
+}
+
+// Every combo of a class
+fn get_class_combos(high_rank: u8, low_rank: u8, suitedness: Suitedness) -> Vec<usize> {
+    let mut combos = Vec::new();
+    for high_suit in 0..4 {
+        for low_suit in 0..4 {
+            let suited = high_suit == low_suit;
+            if (high_rank == low_rank && high_suit >= low_suit) || (suited && suitedness == Suitedness::Offsuit) || (!suited && suitedness == Suitedness::Suited) {
+                continue
+            }
+            combos.push(get_combo_index(deck_make_card(high_suit, high_rank), deck_make_card(low_suit, low_rank)));
+        }
+    }
+    return combos
+}
+
+// The combos of a part of a range without its weight
+fn parse_part(part: &str) -> Result<Vec<usize>, String> {
+    // A single combo like "AhKd"
+    if part.len() == 4 && part.is_ascii() && part.chars().nth(1).is_some_and(|suit| "shdc".contains(suit)) {
+        let (first_card, second_card) = (parse_card(&part[..2])?, parse_card(&part[2..])?);
+        if first_card == second_card {
+            return Err(format!("invalid combo \"{}\"", part))
+        }
+        return Ok(vec![get_combo_index(first_card, second_card)])
+    }
+
+    // Every class from the first to the last, which share the high card or are both pairs
+    let (first, last) = if let Some(first) = part.strip_suffix('+') {
+        let (high_rank, low_rank, _) = parse_class(first)?;
+        if high_rank == low_rank {
+            (first.to_string(), format!("{}{}", RANK_TO_CHAR[12], RANK_TO_CHAR[12]))
+        } else {
+            (first.to_string(), format!("{}{}{}", RANK_TO_CHAR[high_rank as usize], RANK_TO_CHAR[high_rank as usize - 1], &first[2..]))
+        }
+    } else if let Some((first, last)) = part.split_once('-') {
+        (first.to_string(), last.to_string())
+    } else {
+        (part.to_string(), part.to_string())
+    };
+    let (first_high_rank, first_low_rank, suitedness) = parse_class(&first)?;
+    let (last_high_rank, last_low_rank, last_suitedness) = parse_class(&last)?;
+    let is_pair_range = first_high_rank == first_low_rank && last_high_rank == last_low_rank;
+    if suitedness != last_suitedness || (!is_pair_range && first_high_rank != last_high_rank) {
+        return Err(format!("invalid hand range \"{}\"", part))
+    }
+
+    let mut combos = Vec::new();
+    for low_rank in first_low_rank.min(last_low_rank)..=first_low_rank.max(last_low_rank) {
+        let high_rank = if is_pair_range { low_rank } else { first_high_rank };
+        combos.extend(get_class_combos(high_rank, low_rank, suitedness));
+    }
+    return Ok(combos)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Range {
+    pub weights: RangeWeights,
+}
+
+impl Range {
+    pub fn parse(notation: &str) -> Result<Self, String> {
+        let mut weights = [0.0; HOLE_CARD_COMBOS];
+        for part in notation.split(',').map(|part| part.trim()).filter(|part| !part.is_empty()) {
+            let (hands, weight) = match part.split_once(':') {
+                Some((hands, weight)) => {
+                    let weight = weight.trim().parse::<f32>().map_err(|_| format!("invalid weight in \"{}\"", part))?;
+                    if !(0.0..=1.0).contains(&weight) {
+                        return Err(format!("weight in \"{}\" is not between 0 and 1", part))
+                    }
+                    (hands.trim(), weight)
+                },
+                None => (part, 1.0),
+            };
+            for combo_index in parse_part(hands)? {
+                weights[combo_index] = weight;
+            }
+        }
+        return Ok(Range { weights })
+    }
+
+    // The combos with a weight above zero
+    pub fn get_combos(&self) -> Vec<([Card; PRIVATE_CARD_AMOUNT], f32)> {
+        return (0..HOLE_CARD_COMBOS)
+            .filter(|&combo_index| self.weights[combo_index] > 0.0)
+            .map(|combo_index| (get_hole_card_combo(combo_index), self.weights[combo_index]))
+            .collect()
+    }
+
+    // Combos that hold any of the known cards (a board, dead cards or the hand of another player) can't be dealt
+    pub fn remove_blocked(&mut self, known_cards: &[Card]) {
+        for (combo_index, weight) in self.weights.iter_mut().enumerate() {
+            if get_hole_card_combo(combo_index).iter().any(|card| known_cards.contains(card)) {
+                *weight = 0.0;
+            }
+        }
+    }
+
+    // The average weight of the combos of a preflop class
+    pub fn get_preflop_class_weight(&self, preflop_class: usize) -> f32 {
+        let class_weights = (0..HOLE_CARD_COMBOS)
+            .filter(|&combo_index| get_preflop_class(get_hole_card_combo(combo_index)) == preflop_class)
+            .map(|combo_index| self.weights[combo_index])
+            .collect::<Vec<f32>>();
+        return class_weights.iter().sum::<f32>() / class_weights.len() as f32
+    }
+
+    /*
+        The range as the 13x13 grid of preflop classes, a line per row.
+        A class in the range shows its name, followed by its weight when that is below 1 ("AKs:0.50"), a class outside of it shows a dot.
+    */
+    pub fn to_grid(&self) -> String {
+        let mut class_weights = [0.0; PREFLOP_CLASSES];
+        let mut class_combo_amounts = [0; PREFLOP_CLASSES];
+        for combo_index in 0..HOLE_CARD_COMBOS {
+            let preflop_class = get_preflop_class(get_hole_card_combo(combo_index));
+            class_weights[preflop_class] += self.weights[combo_index];
+            class_combo_amounts[preflop_class] += 1;
+        }
+
+        let mut grid = String::new();
+        for row in 0..RANK_AMOUNT as usize {
+            let cells = (0..RANK_AMOUNT as usize).map(|column| {
+                let preflop_class = row * RANK_AMOUNT as usize + column;
+                let weight = class_weights[preflop_class] / class_combo_amounts[preflop_class] as f32;
+                let cell = match weight {
+                    weight if weight <= 0.0 => ".".to_string(),
+                    weight if weight >= 1.0 => get_preflop_class_name(preflop_class),
+                    weight => format!("{}:{:.2}", get_preflop_class_name(preflop_class), weight),
+                };
+                return format!("{:<width$}", cell, width = GRID_CELL_WIDTH)
+            }).collect::<String>();
+            grid.push_str(cells.trim_end());
+            grid.push('\n');
+        }
+        return grid
+    }
+}
@@ -1,54 +1,28 @@
-use std::collections::HashMap;
-
-use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
-pub type ActionIdentifier = u8;
+use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
 
-lazy_static! {
-
-    pub static ref PREDEFINED_ACTION_ID_TO_ACTION_WITH_RAISE: HashMap<ActionIdentifier, Action> = {
-        let mut result = HashMap::new();
-        result.insert(0, Action { action_type: ActionType::Fold, raise_amount: 0 });
-        result.insert(1, Action { action_type: ActionType::Call, raise_amount: 0 });
-        result.insert(2, Action { action_type: ActionType::AllIn, raise_amount: 0 });
-        result.insert(3, Action { action_type: ActionType::Bet, raise_amount: 0 });
-        result.insert(4, Action { action_type: ActionType::Bet, raise_amount: 25 });
-        result.insert(5, Action { action_type: ActionType::Bet, raise_amount: 50 });
-        result.insert(6, Action { action_type: ActionType::Bet, raise_amount: 75 });
-        result.insert(7, Action { action_type: ActionType::Bet, raise_amount: 80 });
-        result.insert(8, Action { action_type: ActionType::Bet, raise_amount: 100 });
-        result.insert(9, Action { action_type: ActionType::Bet, raise_amount: 134 });
-        result.insert(10, Action { action_type: ActionType::Bet, raise_amount: 150 });
-        result.insert(11, Action { action_type: ActionType::Bet, raise_amount: 200 });
-        result.insert(12, Action { action_type: ActionType::Bet, raise_amount: 400 });
-        result.insert(13, Action { action_type: ActionType::Bet, raise_amount: 700 });
-        result.insert(14, Action { action_type: ActionType::Bet, raise_amount: 800 });
-        result.insert(15, Action { action_type: ActionType::Bet, raise_amount: 1000 });
-        result.insert(16, Action { action_type: ActionType::Bet, raise_amount: 1300 });
-        result.insert(17, Action { action_type: ActionType::Bet, raise_amount: 1500 });
-        result.insert(18, Action { action_type: ActionType::Bet, raise_amount: 2500 });
-
-        return result;
-    };
-
-    pub static ref PREDEFINED_ACTION_WITH_RAISE_TO_ACTION_ID: HashMap<Action, ActionIdentifier> = {
-        return PREDEFINED_ACTION_ID_TO_ACTION_WITH_RAISE.iter()
-            .map(|(&id, action)| (action.clone(), id))
-            .collect();
-    };
-}
+pub type ActionIdentifier = u8;
 
+/*
+    Check and Raise are what the NLTH game state stores for a call without chips to call and a bet when someone already bet
+    this round (the blinds count as bets). Callers may pass Call and Bet for either, handle_action derives the right one.
+    Kuhn and Leduc poker only use Call and Bet.
+*/
 #[derive(Eq, Hash, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ActionType {
-    #[serde(rename(serialize = "fold"))]
+    #[serde(rename = "fold", alias = "Fold")]
     Fold,
-    #[serde(rename(serialize = "call"))]
+    #[serde(rename = "call", alias = "Call")]
     Call,
-    #[serde(rename(serialize = "bet"))]
+    #[serde(rename = "bet", alias = "Bet")]
     Bet,
-    #[serde(rename(serialize = "all_in"))]
+    #[serde(rename = "all_in", alias = "AllIn")]
     AllIn,
+    #[serde(rename = "check", alias = "Check")]
+    Check,
+    #[serde(rename = "raise", alias = "Raise")]
+    Raise,
 }
 impl ActionType {
     pub fn from_string(action_type_string: &str) -> Option<Self> {
@@ -57,6 +31,8 @@ impl ActionType {
             "call"      => return Some(ActionType::Call),
             "bet"       => return Some(ActionType::Bet),
             "all_in"    => return Some(ActionType::AllIn),
+            "check"     => return Some(ActionType::Check),
+            "raise"     => return Some(ActionType::Raise),
             _ => return None
         };
     }
@@ -66,41 +42,173 @@ impl ActionType {
             ActionType::Call        => "call".to_owned(),
             ActionType::Bet         => "bet".to_owned(),
             ActionType::AllIn       => "all_in".to_owned(),
+            ActionType::Check       => "check".to_owned(),
+            ActionType::Raise       => "raise".to_owned(),
+        }
+    }
+
+    // Check as Call and Raise as Bet
+    pub fn without_check_raise(&self) -> Self {
+        match self {
+            ActionType::Check   => ActionType::Call,
+            ActionType::Raise   => ActionType::Bet,
+            _ => *self
+        }
+    }
+}
+
+/*
+    How the raise_amount of a bet is turned into chips. Every sizing gives the total bet of the round the player raises to,
+    rounded with the BetRounding of the game state (see resolve_bet_amount):
+        PotFraction: (pot + call amount) * raise_amount / 100
+        BigBlinds: big blind * raise_amount / 100, so 250 is a raise to 2.5 big blinds
+        LastRaise: the highest bet of the round * raise_amount / 100, so 300 is a 3x raise. Not available before anyone has bet this round
+        Chips: raise_amount chips
+*/
+#[derive(Eq, Hash, Clone, Copy, Debug, Default, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BetSizing {
+    #[default]
+    #[serde(rename = "pot")]
+    PotFraction,
+    #[serde(rename = "big_blinds")]
+    BigBlinds,
+    #[serde(rename = "last_raise")]
+    LastRaise,
+    #[serde(rename = "chips")]
+    Chips,
+}
+impl BetSizing {
+    pub fn is_pot_fraction(&self) -> bool {
+        return self == &BetSizing::PotFraction
+    }
+
+    // Formats a raise_amount like "x0.75" (pot), "2.5bb", "3x" (last raise) or "250c" (chips)
+    pub fn format_amount(&self, raise_amount: u16) -> String {
+        match self {
+            BetSizing::PotFraction  => format!("x{}", raise_amount as f32 / 100.0),
+            BetSizing::BigBlinds    => format!("{}bb", raise_amount as f32 / 100.0),
+            BetSizing::LastRaise    => format!("{}x", raise_amount as f32 / 100.0),
+            BetSizing::Chips        => format!("{}c", raise_amount),
+        }
+    }
+
+    // The inverse of format_amount
+    pub fn parse_amount(value: &str) -> Option<(Self, u16)> {
+        let hundredths = |number: &str| {
+            let number = number.parse::<f32>().ok()?;
+            if !(0.0..=u16::MAX as f32 / 100.0).contains(&number) {
+                return None
+            }
+            return Some((number * 100.0).round() as u16)
+        };
+        if let Some(number) = value.strip_prefix('x') {
+            return Some((BetSizing::PotFraction, hundredths(number)?))
         }
+        if let Some(number) = value.strip_suffix("bb") {
+            return Some((BetSizing::BigBlinds, hundredths(number)?))
+        }
+        if let Some(number) = value.strip_suffix('x') {
+            return Some((BetSizing::LastRaise, hundredths(number)?))
+        }
+        if let Some(number) = value.strip_suffix('c') {
+            return Some((BetSizing::Chips, number.parse::<u16>().ok()?))
+        }
+        return None
+    }
+}
+
+/*
+    How a bet size that isn't a whole amount of chips is rounded: Down, to the Nearest (halves round up) or Up,
+    to a multiple of the unit. Every sizing except Chips goes through it. The default rounds down to a single chip.
+*/
+#[derive(Eq, Hash, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum RoundingMode {
+    #[default]
+    #[serde(rename = "down")]
+    Down,
+    #[serde(rename = "nearest")]
+    Nearest,
+    #[serde(rename = "up")]
+    Up,
+}
+
+#[derive(Eq, Hash, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RoundingUnit {
+    #[serde(rename = "chips")]
+    Chips(u32),
+    #[serde(rename = "big_blind")]
+    BigBlind,
+}
+impl Default for RoundingUnit {
+    fn default() -> Self {
+        return RoundingUnit::Chips(1)
+    }
+}
+
+#[derive(Eq, Hash, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BetRounding {
+    pub mode: RoundingMode,
+    pub unit: RoundingUnit,
+}
+impl BetRounding {
+    // Rounds numerator / denominator chips, exactly
+    pub fn round(&self, numerator: u64, denominator: u64, big_blind: u32) -> u32 {
+        let unit = match self.unit {
+            RoundingUnit::Chips(chips) => chips.max(1) as u64,
+            RoundingUnit::BigBlind => big_blind.max(1) as u64,
+        };
+        let denominator = denominator * unit;
+        let units = match self.mode {
+            RoundingMode::Down => numerator / denominator,
+            RoundingMode::Nearest => (2 * numerator + denominator) / (2 * denominator),
+            RoundingMode::Up => numerator.div_ceil(denominator),
+        };
+        return (units * unit).min(u32::MAX as u64) as u32
     }
 }
 
 #[derive(Eq, Hash, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Action {
     pub action_type: ActionType,
-    pub raise_amount: u16
+    pub raise_amount: u16,
+    #[serde(default, skip_serializing_if = "BetSizing::is_pot_fraction")]
+    pub sizing: BetSizing,
 }
 
 impl Action {
     pub fn as_string(&self) -> String {
         if self.raise_amount != 0 {
-            return format!("{:?} x{}", self.action_type, self.raise_amount as f32 / 100.0)
+            return format!("{:?} {}", self.action_type, self.sizing.format_amount(self.raise_amount))
         }
         return format!("{:?}", self.action_type)
     }
+    // The raise_amount as a multiplier, in the unit of the sizing
     pub fn get_multiplier(&self) -> f32 {
         return self.raise_amount as f32 / 100.0
     }
 
     pub fn from_string(value: &str) -> Option<Self> {
         if let Ok(value_u8) = value.parse::<ActionIdentifier>() {
-            if let Some(action) = PREDEFINED_ACTION_ID_TO_ACTION_WITH_RAISE.get(&value_u8) {
-                return Some(action.clone());
-            }
+            return DEFAULT_ACTION_ABSTRACTION.get_action(value_u8);
         }
         return None;
     }
 
+    // The ActionIdentifier in the default action abstraction. A check has the identifier of a call and a raise that of the bet
     pub fn into_identifier(&self) -> Option<&ActionIdentifier> {
-        return PREDEFINED_ACTION_WITH_RAISE_TO_ACTION_ID.get(&self)
+        return DEFAULT_ACTION_ABSTRACTION.action_to_identifier.get(&self.without_check_raise())
+    }
+
+    // The action with Check as Call and Raise as Bet, the way action identifiers, Kuhn and Leduc poker see it
+    pub fn without_check_raise(&self) -> Self {
+        return Action { action_type: self.action_type.without_check_raise(), ..*self }
     }
 
     pub fn is_bet_raise(&self) -> bool {
-        return self.action_type == ActionType::Bet;
+        return self.action_type == ActionType::Bet || self.action_type == ActionType::Raise;
+    }
+
+    pub fn is_check_call(&self) -> bool {
+        return self.action_type == ActionType::Call || self.action_type == ActionType::Check;
     }
 }
@@ -1,95 +1,154 @@
-use lazy_static::lazy_static;
-use smallvec::{smallvec, SmallVec};
-
-use crate::structs::{ActionType, Action};
-
-lazy_static! {
-    pub static ref AVAILABLE_ACTIONS: [SmallVec<[SmallVec<[Action; 40]>; 8]>; 4] = [
-        // Round 0
-        smallvec![
-            // Initial bet
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 75 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-                Action { action_type: ActionType::Bet, raise_amount: 134 },
-                Action { action_type: ActionType::Bet, raise_amount: 150 },
-                Action { action_type: ActionType::Bet, raise_amount: 200 },
-                Action { action_type: ActionType::Bet, raise_amount: 400 },
-                Action { action_type: ActionType::Bet, raise_amount: 800 },
-                Action { action_type: ActionType::Bet, raise_amount: 1300 },
-                Action { action_type: ActionType::Bet, raise_amount: 1500 },
-                Action { action_type: ActionType::Bet, raise_amount: 2500 },
-            ],
-            // Raise
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 75 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-                Action { action_type: ActionType::Bet, raise_amount: 200 },
-                Action { action_type: ActionType::Bet, raise_amount: 400 },
-                Action { action_type: ActionType::Bet, raise_amount: 700 },
-                Action { action_type: ActionType::Bet, raise_amount: 1000 },
-                Action { action_type: ActionType::Bet, raise_amount: 1500 },
-            ],
-            // Three-bets
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 75 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-                Action { action_type: ActionType::Bet, raise_amount: 200 },
-                Action { action_type: ActionType::Bet, raise_amount: 400 },
-                Action { action_type: ActionType::Bet, raise_amount: 800 },
-            ],
-            // Four-bets
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-        ],
-        // Round 1
-        smallvec![
-            // Initial bet
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-                Action { action_type: ActionType::Bet, raise_amount: 200 },
-                Action { action_type: ActionType::Bet, raise_amount: 400 },
-                Action { action_type: ActionType::Bet, raise_amount: 700 },
-                Action { action_type: ActionType::Bet, raise_amount: 1300 },
-            ],
-            // Raise
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-                Action { action_type: ActionType::Bet, raise_amount: 200 },
-            ],
-            // Three-bets
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-        ],
-        // Round 2
-        smallvec![
-            // Initial bet
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-            // Raise
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-        ],
-        // Round 3
-        smallvec![
-            // Initial bet
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 50 },
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-            // Raise
-            smallvec![
-                Action { action_type: ActionType::Bet, raise_amount: 100 },
-            ],
-        ],
-    ];
-}
\ No newline at end of file
+#[cfg(test)]
+mod action_abstraction_tests {
+    use std::fs;
+
+    use crate::action_abstraction::{ActionAbstraction, ActionAbstractionConfig, BetSize, DEFAULT_ACTION_ABSTRACTION};
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::structs::{ActionType, Action, BetSizing};
+
+    const POSITION_ABSTRACTION: &str = r#"
+        [[rounds]]
+        raise_depths = [[100, 200], [100]]
+
+        [[rounds.positions]]
+        player_index = 0
+        raise_depths = [[300]]
+
+        [[rounds]]
+        raise_depths = [[50]]
+    "#;
+
+    fn bet(raise_amount: u16) -> Action {
+        return Action { action_type: ActionType::Bet, raise_amount, sizing: BetSizing::PotFraction }
+    }
+
+    #[test]
+    fn test_default_identifiers() {
+        let abstraction = &DEFAULT_ACTION_ABSTRACTION;
+        assert_eq!(abstraction.action_amount(), 19);
+        assert_eq!(abstraction.get_action(0), Some(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        assert_eq!(abstraction.get_action(1), Some(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        assert_eq!(abstraction.get_action(2), Some(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        assert_eq!(abstraction.get_action(3), Some(bet(0)));
+        assert_eq!(abstraction.get_action(8), Some(bet(100)));
+        assert_eq!(abstraction.get_action(18), Some(bet(2500)));
+        assert_eq!(abstraction.get_action(19), None);
+        assert_eq!(abstraction.get_identifier(&bet(134)), Some(9));
+        assert_eq!(bet(1300).into_identifier(), Some(&16));
+        assert_eq!(Action::from_string("7"), Some(bet(80)));
+    }
+
+    #[test]
+    fn test_default_bets() {
+        let abstraction = &DEFAULT_ACTION_ABSTRACTION;
+        assert_eq!(abstraction.get_bets(0, 3, 0).unwrap().as_slice(), &[bet(50), bet(100)]);
+        assert_eq!(abstraction.get_bets(0, 4, 0), None);
+        assert_eq!(abstraction.get_bets(3, 1, 5).unwrap().as_slice(), &[bet(100)]);
+        assert_eq!(abstraction.get_bets(4, 0, 0), None);
+
+        let game_state = NLTHGameState::new_empty(2, false, None);
+        assert_eq!(abstraction.get_bets_in_abstraction(&game_state), abstraction.get_bets(0, 0, 0));
+        assert_eq!(
+            abstraction.get_active_player_actions(&game_state),
+            game_state.get_active_player_actions(abstraction.get_bets(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_position_bet_sizes() {
+        let abstraction = ActionAbstraction::from_toml(POSITION_ABSTRACTION).unwrap();
+        // Bet sizes that aren't listed explicitly get identifiers in ascending order
+        assert_eq!(abstraction.config.bet_sizes, [50, 100, 200, 300].map(BetSize::pot_fraction));
+        assert_eq!(abstraction.get_identifier(&bet(300)), Some(7));
+
+        let mut game_state = NLTHGameState::new_empty(2, false, None);
+        assert_eq!(abstraction.get_active_player_actions(&game_state).as_slice(), &[
+            Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction },
+            bet(300),
+        ]);
+        // Player 0 has no raise sizes, player 1 uses the sizes of the round
+        assert_eq!(abstraction.get_bets(0, 1, 0), None);
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(abstraction.get_bets_in_abstraction(&game_state).unwrap().as_slice(), &[bet(100), bet(200)]);
+        game_state = game_state.handle_action(bet(200));
+        assert_eq!(abstraction.get_bets_in_abstraction(&game_state), None);
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(abstraction.get_bets_in_abstraction(&game_state).unwrap().as_slice(), &[bet(50)]);
+        // There are no bet sizes for the turn and the river
+        assert_eq!(abstraction.get_bets(2, 0, 1), None);
+    }
+
+    #[test]
+    fn test_bet_sizings() {
+        let abstraction = ActionAbstraction::from_toml(r#"
+            [[rounds]]
+            raise_depths = [["2.5bb", "3bb", 100], ["3x", "250c"]]
+        "#).unwrap();
+        // Pot fractions come first
+        assert_eq!(abstraction.get_action(4), Some(bet(100)));
+        assert_eq!(abstraction.get_action(5), Some(Action { action_type: ActionType::Bet, raise_amount: 250, sizing: BetSizing::BigBlinds }));
+        assert_eq!(abstraction.get_action(7), Some(Action { action_type: ActionType::Bet, raise_amount: 300, sizing: BetSizing::LastRaise }));
+        assert_eq!(abstraction.get_action(8), Some(Action { action_type: ActionType::Bet, raise_amount: 250, sizing: BetSizing::Chips }));
+        assert!(abstraction.to_toml().contains("[[\"2.5bb\", \"3bb\", 100], [\"3x\", \"250c\"]]"));
+        assert_eq!(ActionAbstraction::from_json(&abstraction.to_json()).unwrap().config, abstraction.config);
+
+        assert!(ActionAbstraction::from_toml("[[rounds]]\nraise_depths = [[\"3y\"]]").is_err());
+        assert!(ActionAbstraction::from_toml("[[rounds]]\nraise_depths = [[\"0bb\"]]").is_err());
+        assert!(ActionAbstraction::from_toml("[[rounds]]\nraise_depths = [[\"70000c\"]]").is_err());
+    }
+
+    #[test]
+    fn test_all_in_threshold_and_merging() {
+        let abstraction = ActionAbstraction::from_toml(r#"
+            all_in_threshold = 50
+            merge_threshold = 10
+            [[rounds]]
+            raise_depths = [[100, "2.5bb", "250c", "2.6bb", "40bb", "60bb"]]
+        "#).unwrap();
+        let big_blinds = |raise_amount: u16| Action { action_type: ActionType::Bet, raise_amount, sizing: BetSizing::BigBlinds };
+        let game_state = NLTHGameState::new_empty(2, false, None);
+        // 250c moves the same 200 chips as 2.5bb and 2.6bb is within 10% of it, 60bb would commit more than half of the stack
+        assert_eq!(abstraction.get_active_player_actions(&game_state).as_slice(), &[
+            Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction },
+            bet(100),
+            big_blinds(250),
+            big_blinds(4000),
+        ]);
+
+        // Without the options every legal bet is available
+        let abstraction = ActionAbstraction::new(ActionAbstractionConfig { all_in_threshold: None, merge_threshold: None, ..abstraction.config }).unwrap();
+        assert_eq!(abstraction.get_active_player_actions(&game_state).len(), 9);
+        assert!(ActionAbstraction::from_toml("all_in_threshold = 0\n[[rounds]]\nraise_depths = [[50]]").is_err());
+    }
+
+    #[test]
+    fn test_formats_round_trip() {
+        let abstraction = ActionAbstraction::from_toml(&format!("merge_threshold = 5\n{}", POSITION_ABSTRACTION)).unwrap();
+        assert_eq!(ActionAbstraction::from_toml(&abstraction.to_toml()).unwrap().config, abstraction.config);
+        assert_eq!(ActionAbstraction::from_json(&abstraction.to_json()).unwrap().config, abstraction.config);
+        assert_eq!(ActionAbstraction::from_toml(&DEFAULT_ACTION_ABSTRACTION.to_toml()).unwrap().config, DEFAULT_ACTION_ABSTRACTION.config);
+
+        let path = std::env::temp_dir().join(format!("action_abstraction_{}.json", std::process::id()));
+        fs::write(&path, abstraction.to_json()).unwrap();
+        let loaded = ActionAbstraction::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().config, abstraction.config);
+    }
+
+    #[test]
+    fn test_invalid_abstractions() {
+        assert!(ActionAbstraction::from_toml("rounds = []").is_err());
+        assert!(ActionAbstraction::from_toml("[[rounds]]\nraise_depths = [[0]]").is_err());
+        assert!(ActionAbstraction::from_toml("bet_sizes = [100]\n[[rounds]]\nraise_depths = [[50]]").is_err());
+        assert!(ActionAbstraction::from_toml("bet_sizes = [50, 50]\n[[rounds]]\nraise_depths = [[50]]").is_err());
+        assert!(ActionAbstraction::from_toml(&"[[rounds]]\nraise_depths = [[50]]\n".repeat(5)).is_err());
+        assert!(ActionAbstraction::from_toml("[[rounds]]\nraise_depths = [[50]]\n[[rounds.positions]]\nplayer_index = 6\nraise_depths = []").is_err());
+        assert!(ActionAbstraction::from_json("{\"rounds\": [{\"raise_depths\": [[50]]}]}").is_ok());
+        assert!(ActionAbstraction::from_json("{\"rounds\": [{\"raise_depths\": [[-50]]}]}").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod action_translation_tests {
+    use rand::prelude::*;
+
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+    use crate::action_translation::{pseudo_harmonic_probability, sample_translation, translate_bet, TranslationMethod};
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::structs::{ActionType, Action, BetSizing};
+
+    // Heads-up flop with 200 in the pot, the default abstraction has flop bets of 0.5, 1, 2, 4, 7 and 13 times the pot
+    fn setup_flop() -> NLTHGameState {
+        let mut game_state = NLTHGameState::new_empty(2, true, Some(3));
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!((game_state.round, game_state.get_total_pot()), (1, 200));
+        return game_state
+    }
+
+    fn bet(raise_amount: u16) -> Action {
+        return Action { action_type: ActionType::Bet, raise_amount, sizing: BetSizing::PotFraction }
+    }
+
+    #[test]
+    fn test_pseudo_harmonic_probability() {
+        assert_eq!(pseudo_harmonic_probability(0.5, 1.0, 0.5), 1.0);
+        assert_eq!(pseudo_harmonic_probability(0.5, 1.0, 1.0), 0.0);
+        assert!((pseudo_harmonic_probability(0.5, 1.0, 0.75) - 3.0 / 7.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_translation_methods() {
+        let game_state = setup_flop();
+        let bettor = game_state.active_player_index;
+
+        // 150 chips is 0.75 times the pot
+        let translated_actions = translate_bet(&game_state, 150, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::PseudoHarmonic).unwrap();
+        assert_eq!(translated_actions.len(), 2);
+        assert_eq!((translated_actions[0].action, translated_actions[1].action), (bet(50), bet(100)));
+        assert!((translated_actions[0].probability - 3.0 / 7.0).abs() < 1e-6);
+        assert!((translated_actions[1].probability - 4.0 / 7.0).abs() < 1e-6);
+
+        let translated_actions = translate_bet(&game_state, 150, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::DeterministicPseudoHarmonic).unwrap();
+        assert_eq!(translated_actions.len(), 1);
+        assert_eq!((translated_actions[0].action, translated_actions[0].probability), (bet(100), 1.0));
+
+        // Equally close to both, so the smaller one
+        let translated_actions = translate_bet(&game_state, 150, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::NearestNeighbour).unwrap();
+        assert_eq!(translated_actions[0].action, bet(50));
+        let translated_actions = translate_bet(&game_state, 160, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::NearestNeighbour).unwrap();
+        assert_eq!(translated_actions[0].action, bet(100));
+
+        // The translated state has the abstract action in its history, but the real pot and stacks
+        let translated_state = &translate_bet(&game_state, 150, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::DeterministicPseudoHarmonic).unwrap()[0].translated_state;
+        assert_eq!(translated_state.history[1].last(), Some(&bet(100)));
+        assert_eq!(translated_state.get_total_pot(), 350);
+        assert_eq!(translated_state.stacks[bettor], 9750);
+        assert_eq!(translated_state.minimum_raise_amount, 150);
+        assert_eq!(translated_state.get_current_bet_count(), 1);
+        assert_eq!(translated_state.get_call_amount(), 150);
+    }
+
+    #[test]
+    fn test_exact_and_out_of_range_bets() {
+        let game_state = setup_flop();
+        for method in [TranslationMethod::PseudoHarmonic, TranslationMethod::DeterministicPseudoHarmonic, TranslationMethod::NearestNeighbour] {
+            let translated_actions = translate_bet(&game_state, 400, &DEFAULT_ACTION_ABSTRACTION, method).unwrap();
+            assert_eq!((translated_actions.len(), translated_actions[0].action), (1, bet(200)));
+            assert_eq!(format!("{:?}", translated_actions[0].translated_state), format!("{:?}", game_state.handle_action(bet(200))));
+
+            // Larger than the 13 times pot bet
+            let translated_actions = translate_bet(&game_state, 3000, &DEFAULT_ACTION_ABSTRACTION, method).unwrap();
+            assert_eq!((translated_actions.len(), translated_actions[0].action), (1, bet(1300)));
+            assert_eq!(translated_actions[0].translated_state.get_total_pot(), 3200);
+
+            let translated_actions = translate_bet(&game_state, 0, &DEFAULT_ACTION_ABSTRACTION, method).unwrap();
+            assert_eq!(translated_actions[0].action, Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+            let translated_actions = translate_bet(&game_state, 9900, &DEFAULT_ACTION_ABSTRACTION, method).unwrap();
+            assert_eq!(translated_actions[0].action, Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+
+        // Below the minimum raise and more than the stack
+        assert!(translate_bet(&game_state, 50, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::PseudoHarmonic).is_err());
+        assert!(translate_bet(&game_state, 9901, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::PseudoHarmonic).is_err());
+    }
+
+    #[test]
+    fn test_sample_translation() {
+        let game_state = setup_flop();
+        let translated_actions = translate_bet(&game_state, 150, &DEFAULT_ACTION_ABSTRACTION, TranslationMethod::PseudoHarmonic).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let smaller_amount = (0..10_000).filter(|_| sample_translation(&translated_actions, &mut rng).action == bet(50)).count();
+        assert!((4000..4600).contains(&smaller_amount));
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod card_abstraction_tests {
+    use hand_isomorphism_rust::deck::{card_from_string, Card};
+
+    use crate::card_abstraction::{earth_movers_distance, get_hand_features, get_potential_aware_features, hand_strength, k_means, BucketTable, CardAbstraction, HandFeature};
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::structs::{Action, ActionType, BetSizing};
+
+    fn cards(cards: &str) -> Vec<Card> {
+        return cards.split_whitespace().map(|card| card_from_string(card.to_string())).collect()
+    }
+
+    fn hole_cards(hole_cards: &str) -> [Card; 2] {
+        return cards(hole_cards).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_hand_strength_features() {
+        let board: [Card; 5] = cards("Qh Jh Th 2c 3d").try_into().unwrap();
+        assert_eq!(hand_strength(hole_cards("Ah Kh"), &board), 1.0);
+        assert!(hand_strength(hole_cards("4s 5c"), &board) < 0.2);
+
+        let turn_board = cards("Qh Jh 2c 3d");
+        let ehs = get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrength, 100, None).unwrap()[0];
+        let ehs_squared = get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrengthSquared, 100, None).unwrap()[0];
+        assert!(ehs_squared <= ehs && ehs_squared >= ehs * ehs);
+        let distribution = get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::HandStrengthDistribution(10), 100, None).unwrap();
+        assert_eq!(distribution.len(), 10);
+        assert!((distribution.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // Any heart or ten makes the nuts
+        assert!(distribution[9] > 0.2);
+        assert!(get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrength, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_invalid_features() {
+        let turn_board = cards("Qh Jh 2c 3d");
+        assert!(get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::HandStrengthDistribution(0), 10, None).is_err());
+        assert!(get_hand_features(hole_cards("Ah Kh"), &cards("Qh Jh 2c 3d 4s 5s"), HandFeature::ExpectedHandStrength, 10, None).is_err());
+        assert!(get_hand_features(hole_cards("Ah Kh"), &cards("Qh Jh Ah"), HandFeature::ExpectedHandStrength, 10, None).is_err());
+        assert!(get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::PotentialAware(10), 10, None).is_err());
+        let hands = vec![(hole_cards("Ah Kh"), turn_board.clone())];
+        assert!(BucketTable::build(2, &hands, HandFeature::HandStrengthDistribution(0), 2, 20, 10, None).is_err());
+        assert!(BucketTable::build(2, &hands, HandFeature::PotentialAware(10), 2, 20, 10, None).is_err());
+    }
+
+    #[test]
+    fn test_potential_aware_bucket_table() {
+        let turn_board = cards("Qh Jh 2c 3d");
+        let turn_hands = ["Ah Kh", "7s 8s", "Qs Qd"].iter().map(|&hand| (hole_cards(hand), turn_board.clone())).collect::<Vec<_>>();
+        // The river table holds every hand that can follow the turn hands
+        let river_hands = turn_hands.iter().flat_map(|(hole_cards, board)| {
+            return (0..52)
+                .filter(|card| !hole_cards.contains(card) && !board.contains(card))
+                .map(|river_card| (*hole_cards, [&board[..], &[river_card]].concat()))
+                .collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+        let river_table = BucketTable::build(3, &river_hands, HandFeature::ExpectedHandStrength, 5, 20, 1, Some(1)).unwrap();
+        assert!(river_table.bucket_strengths.windows(2).all(|strengths| strengths[0] <= strengths[1]));
+
+        let histogram = get_potential_aware_features(hole_cards("Ah Kh"), &turn_board, &river_table, 46, None).unwrap();
+        assert_eq!(histogram.len(), river_table.bucket_strengths.len());
+        assert!((histogram.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // The draw ends up in stronger river buckets than the weak hand
+        let expected_strength = |hand: &str| {
+            let histogram = get_potential_aware_features(hole_cards(hand), &turn_board, &river_table, 46, None).unwrap();
+            return histogram.iter().zip(river_table.bucket_strengths.iter()).map(|(share, strength)| share * strength).sum::<f32>()
+        };
+        assert!(expected_strength("Ah Kh") > expected_strength("7s 8s"));
+
+        let turn_table = BucketTable::build_potential_aware(2, &turn_hands, &river_table, 2, 20, 46, Some(1)).unwrap();
+        assert_eq!(turn_table.feature, HandFeature::PotentialAware(river_table.bucket_strengths.len()));
+        assert_eq!(turn_table.get_bucket(hole_cards("7s 8s"), &turn_board), Some(0));
+        assert_eq!(turn_table.get_bucket(hole_cards("Qs Qd"), &turn_board), Some(1));
+        assert_eq!(BucketTable::from_bytes(&turn_table.to_bytes()).unwrap(), turn_table);
+
+        // Hands whose river hands aren't in the table, and tables of another round, can't be used
+        assert!(BucketTable::build_potential_aware(2, &[(hole_cards("As Ks"), turn_board.clone())], &river_table, 2, 20, 46, None).is_err());
+        assert!(BucketTable::build_potential_aware(1, &[(hole_cards("Ah Kh"), cards("Qh Jh 2c"))], &river_table, 2, 20, 46, None).is_err());
+        assert!(get_potential_aware_features(hole_cards("Ah Kh"), &turn_board, &turn_table, 46, None).is_err());
+    }
+
+    #[test]
+    fn test_sampled_runouts() {
+        // The turn has 46 runouts, so 20 samples are random runouts that depend on the seed
+        let turn_board = cards("Qh Jh 2c 3d");
+        let sampled_ehs = get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrength, 20, Some(1)).unwrap()[0];
+        assert_eq!(get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrength, 20, Some(1)).unwrap()[0], sampled_ehs);
+        let ehs = get_hand_features(hole_cards("Ah Kh"), &turn_board, HandFeature::ExpectedHandStrength, 46, None).unwrap()[0];
+        assert!((sampled_ehs - ehs).abs() < 0.1);
+
+        // Without sampling a preflop table would go over 2,118,760 runouts per hand
+        let hands = ["Ah As", "Kh Ks", "7h 2c", "3h 2c"].iter()
+            .map(|&hand| (hole_cards(hand), Vec::new()))
+            .collect::<Vec<_>>();
+        let table = BucketTable::build(0, &hands, HandFeature::ExpectedHandStrength, 2, 20, 10, Some(1)).unwrap();
+        assert_eq!(table.get_bucket(hole_cards("Ac Ad"), &[]), Some(1));
+        assert_eq!(table.get_bucket(hole_cards("7s 2d"), &[]), Some(0));
+        assert!(BucketTable::build(0, &hands, HandFeature::ExpectedHandStrength, 2, 20, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_k_means_and_earth_movers_distance() {
+        assert_eq!(earth_movers_distance(&[1.0, 0.0, 0.0], &[0.0, 0.0, 1.0]), 2.0);
+        assert_eq!(earth_movers_distance(&[0.5, 0.5, 0.0], &[0.5, 0.5, 0.0]), 0.0);
+
+        let points = vec![vec![0.0], vec![0.1], vec![0.9], vec![1.0], vec![0.05]];
+        let clusters = k_means(&points, 2, 10, HandFeature::ExpectedHandStrength, Some(1));
+        assert_eq!(clusters[0], clusters[1]);
+        assert_eq!(clusters[0], clusters[4]);
+        assert_eq!(clusters[2], clusters[3]);
+        assert_ne!(clusters[0], clusters[2]);
+        assert_eq!(k_means(&points, 2, 10, HandFeature::ExpectedHandStrength, Some(1)), clusters);
+    }
+
+    #[test]
+    fn test_river_bucket_table() {
+        let board = cards("Qh Jh Th 2c 3c");
+        let hands = ["Ah Kh", "Kh 9h", "Ad Ks", "As Kd", "Qs Qd", "2s 2d", "4s 5d", "4d 5s", "6c 7s", "Qc 2h"]
+            .iter()
+            .map(|&hand| (hole_cards(hand), board.clone()))
+            .collect::<Vec<_>>();
+        let table = BucketTable::build(3, &hands, HandFeature::ExpectedHandStrength, 3, 20, 1, Some(1)).unwrap();
+        // Diamonds and spades aren't on the board, so "Ad Ks" and "As Kd" are the same hand and so are "4s 5d" and "4d 5s"
+        assert_eq!(table.len(), 8);
+        assert_eq!(table.get_bucket(hole_cards("Ah Kh"), &board), Some(2));
+        assert_eq!(table.get_bucket(hole_cards("4s 5d"), &board), Some(0));
+        assert_eq!(table.get_bucket(hole_cards("Ad Ks"), &board), table.get_bucket(hole_cards("As Kd"), &board));
+        assert_eq!(table.get_bucket(hole_cards("8s 8d"), &board), None);
+        // The table is keyed by the hand index of the river
+        assert_eq!(table.get_bucket(hole_cards("Ah Kh"), &board[..3]), None);
+        assert!(BucketTable::build(3, &[(hole_cards("Ah Kh"), cards("Qh Jh Th"))], HandFeature::ExpectedHandStrength, 3, 20, 1, None).is_err());
+
+        let path = std::env::temp_dir().join(format!("bucket_table_{}.bin", std::process::id()));
+        table.save(&path).unwrap();
+        let loaded = BucketTable::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), table);
+        assert!(BucketTable::from_bytes(&table.to_bytes()[..20]).is_err());
+
+        // The bucket of what a player sees in the state
+        let card_abstraction = CardAbstraction::new(vec![table.clone()]).unwrap();
+        let mut game_state = NLTHGameState::new_empty(2, false, None);
+        game_state.private_hands[0] = hole_cards("Ah Kh");
+        game_state.private_hands[1] = hole_cards("4d 5s");
+        game_state.community_cards = board.clone().try_into().unwrap();
+        assert_eq!(card_abstraction.get_bucket(&game_state, 0), None);
+        while game_state.round < 3 {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        assert_eq!(card_abstraction.get_bucket(&game_state, 0), Some(2));
+        assert_eq!(card_abstraction.get_bucket(&game_state, 1), Some(0));
+        assert!(CardAbstraction::new(vec![table.clone(), table]).is_err());
+    }
+}
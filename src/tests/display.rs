@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod display_tests {
+    use hand_isomorphism_rust::deck::card_from_string;
+
+    use crate::constants::NO_CARD_PLACEHOLDER;
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::leduc_poker::game_state::LPGameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::structs::{ActionType, Action, BetSizing};
+
+    #[test]
+    fn test_nlth_display() {
+        let mut game_state = NLTHGameState::new_empty(3, false, None);
+        game_state.private_hands[0] = [card_from_string("As".to_string()), card_from_string("Ks".to_string())];
+        game_state.community_cards = [
+            card_from_string("Jd".to_string()), card_from_string("Qh".to_string()),
+            card_from_string("Td".to_string()), card_from_string("5s".to_string()),
+            card_from_string("3h".to_string()),
+        ];
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        let rendered = game_state.to_string();
+
+        assert!(rendered.starts_with("NLTH 3-max, Preflop, pot 10350\n"));
+        // The board isn't dealt yet
+        assert!(rendered.contains("Board: \n"));
+        assert!(rendered.contains("AsKs"));
+        assert!(rendered.contains("????"));
+        assert!(rendered.contains("all-in"));
+        assert!(rendered.contains("folded\n"));
+        assert!(rendered.contains("> P2      ????       9750       250\n"));
+        assert!(rendered.contains("Main pot: 10350 (P0, P2)\n"));
+        assert!(rendered.contains("Preflop: Raise x1, AllIn, Fold\n"));
+    }
+
+    #[test]
+    fn test_leduc_and_kuhn_display() {
+        let mut leduc_game_state = LPGameState::new_empty(2, true, Some(1));
+        leduc_game_state = leduc_game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(leduc_game_state.to_string().contains("Round 1: Bet\n"));
+
+        let kuhn_game_state = KPGameState::new_empty(2, true, Some(1));
+        let rendered = kuhn_game_state.to_string();
+        assert!(rendered.starts_with("Kuhn, pot 200\n"));
+        assert!(!rendered.contains(&format!("{}", NO_CARD_PLACEHOLDER)));
+    }
+}
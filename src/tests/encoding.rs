@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod encoding_tests {
+    use std::fmt::Debug;
+
+    use rand::prelude::*;
+    use smallvec::SmallVec;
+
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::encoding::StateEncoding;
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::leduc_poker::game_state::LPGameState;
+    use crate::game_states::nlth_poker::game_state::{NLTHGameState, OddChipRule};
+    use crate::structs::{ActionType, Action, BetRounding, BetSizing, RoundingMode, RoundingUnit};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+
+    fn assert_round_trips<T, F>(player_amount: usize, bets_in_abstraction: F)
+    where
+        T: GameState + StateEncoding + Debug,
+        F: Fn(&T) -> Option<&SmallVec<[Action; 40]>>,
+    {
+        let mut rng = StdRng::seed_from_u64(3);
+        for seed in 0..20 {
+            let mut game_state = T::new_empty(player_amount, true, Some(seed));
+            loop {
+                let bytes = game_state.encode().unwrap();
+                let decoded = T::decode(&bytes).unwrap();
+                assert_eq!(format!("{:?}", decoded), format!("{:?}", game_state));
+                assert_eq!(decoded.state_hash().unwrap(), game_state.state_hash().unwrap());
+
+                if game_state.is_terminal() {
+                    break;
+                }
+                let actions = game_state.get_active_player_actions(bets_in_abstraction(&game_state));
+                let next_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+                assert_ne!(next_state.state_hash().unwrap(), game_state.state_hash().unwrap());
+                game_state = next_state;
+            }
+        }
+    }
+
+    #[test]
+    fn test_nlth_round_trip() {
+        for player_amount in [2, 4, 6] {
+            assert_round_trips::<NLTHGameState, _>(player_amount, |game_state| {
+                DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(game_state)
+            });
+        }
+    }
+
+    #[test]
+    fn test_leduc_round_trip() {
+        assert_round_trips::<LPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_kuhn_round_trip() {
+        assert_round_trips::<KPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_nlth_encoding_is_compact() {
+        let game_state = NLTHGameState::new_empty(6, true, Some(1));
+        // Tag, player amount, 3 one byte blind/ante varints, the bet rounding, the odd chip rule, 6 two byte stacks, 12 private cards, 5 community cards and 4 empty rounds
+        assert_eq!(game_state.encode().unwrap().len(), 1 + 1 + 3 + 3 + 1 + 12 + 12 + 5 + 4);
+    }
+
+    #[test]
+    fn test_nlth_bet_rounding_round_trip() {
+        let mut game_state = NLTHGameState::new_empty(2, true, Some(1));
+        game_state.bet_rounding = BetRounding { mode: RoundingMode::Up, unit: RoundingUnit::BigBlind };
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 75, sizing: BetSizing::PotFraction });
+        let decoded_state = NLTHGameState::decode(&game_state.encode().unwrap()).unwrap();
+        assert_eq!(decoded_state.bet_rounding, game_state.bet_rounding);
+        assert_eq!(decoded_state.bets, game_state.bets);
+
+        // Every unit comes back as it was, also the ones that round the same
+        for unit in [RoundingUnit::Chips(0), RoundingUnit::Chips(1), RoundingUnit::Chips(25), RoundingUnit::BigBlind] {
+            let mut game_state = NLTHGameState::new_empty(2, true, Some(1));
+            game_state.bet_rounding = BetRounding { mode: RoundingMode::Nearest, unit };
+            assert_eq!(NLTHGameState::decode(&game_state.encode().unwrap()).unwrap().bet_rounding.unit, unit);
+        }
+    }
+
+    #[test]
+    fn test_nlth_odd_chip_rule_round_trip() {
+        let fold = Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction };
+        let default_state = NLTHGameState::new_empty(3, true, Some(1)).handle_action(fold);
+        let mut game_state = NLTHGameState::new_empty(3, true, Some(1));
+        game_state.odd_chip_rule = OddChipRule::FirstFromSeat(2);
+        game_state = game_state.handle_action(fold);
+        let decoded_state = NLTHGameState::decode(&game_state.encode().unwrap()).unwrap();
+        assert_eq!(decoded_state.odd_chip_rule, OddChipRule::FirstFromSeat(2));
+        assert_eq!(format!("{:?}", decoded_state), format!("{:?}", game_state));
+        // The rule changes who gets odd chips, so it is part of the hash
+        assert_ne!(decoded_state.state_hash().unwrap(), default_state.state_hash().unwrap());
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let game_state = NLTHGameState::new_empty(2, true, Some(1));
+        // Bets that are not in the ActionIdentifier mapping cannot be encoded
+        assert!(game_state.handle_bet_amount(333).encode().is_err());
+        // A translated bet stores a pot sized bet in the history, but 333 chips went in
+        let pot_bet = Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction };
+        let translated_state = game_state.handle_translated_bet(pot_bet, 333);
+        assert!(translated_state.encode().is_err());
+        assert!(translated_state.state_hash().is_err());
+        assert!(game_state.handle_action(pot_bet).encode().is_ok());
+
+        let bytes = game_state.encode().unwrap();
+        assert!(KPGameState::decode(&bytes).is_err());
+        assert!(NLTHGameState::decode(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing_bytes = bytes.clone();
+        trailing_bytes.push(0);
+        assert!(NLTHGameState::decode(&trailing_bytes).is_err());
+
+        // Like the hand history importers, a player needs more chips than their blind and ante
+        let encode_and_decode = |stacks: [u32; 6]| NLTHGameState::new_with_table(2, stacks, 50, 100, 10).encode().and_then(|bytes| NLTHGameState::decode(&bytes));
+        assert!(encode_and_decode([60, 1000, 0, 0, 0, 0]).is_err());
+        assert!(encode_and_decode([61, 1000, 0, 0, 0, 0]).is_ok());
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod env_tests {
+    use rand::prelude::*;
+
+    use crate::constants::MAX_PLAYERS;
+    use crate::env::{Env, VecEnv};
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::game_states::observation::ObservationTensors;
+    use crate::structs::ActionIdentifier;
+
+    fn legal_action_identifiers(mask: &[bool]) -> Vec<ActionIdentifier> {
+        return mask.iter().enumerate().filter(|(_, &legal)| legal).map(|(action_identifier, _)| action_identifier as ActionIdentifier).collect()
+    }
+
+    #[test]
+    fn test_kuhn_env() {
+        let mut env = Env::<KPGameState>::new(2);
+        let observation = env.reset(1);
+        assert_eq!(observation.len(), KPGameState::tensor_shape().information_state);
+        // Pass (call) and bet
+        assert_eq!(legal_action_identifiers(&env.legal_action_mask()), vec![1, 3]);
+        assert!(env.step(0).is_err());
+        assert!(env.step(200).is_err());
+
+        let (_, rewards, done) = env.step(3).unwrap();
+        assert_eq!((rewards, done), ([0; MAX_PLAYERS], false));
+        assert_eq!(legal_action_identifiers(&env.legal_action_mask()), vec![0, 1]);
+
+        let (observation, rewards, done) = env.step(0).unwrap();
+        assert!(done);
+        assert_eq!(env.current_player_index(), 1);
+        assert_eq!(observation, env.game_state.information_state_tensor(1));
+        assert_eq!(rewards[..2], [100, -100]);
+        assert!(env.legal_action_mask().iter().all(|&legal| !legal));
+        assert!(env.step(1).is_err());
+    }
+
+    #[test]
+    fn test_vec_env() {
+        let env_amount = 64;
+        let shape = NLTHGameState::tensor_shape();
+        let action_amount = Env::<NLTHGameState>::new(3).action_amount();
+        let mut vec_env = VecEnv::<NLTHGameState>::new(3, env_amount);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let observations = vec_env.reset(0);
+        assert_eq!(observations.len(), env_amount * shape.information_state);
+        assert_eq!(observations, VecEnv::<NLTHGameState>::new(3, env_amount).reset(0));
+
+        let mut finished_games = 0;
+        for _ in 0..50 {
+            let masks = vec_env.legal_action_masks();
+            assert_eq!(masks.len(), env_amount * action_amount);
+            let action_identifiers = masks.chunks(action_amount)
+                .map(|mask| *legal_action_identifiers(mask).choose(&mut rng).unwrap())
+                .collect::<Vec<ActionIdentifier>>();
+
+            let (observations, rewards, dones) = vec_env.step(&action_identifiers).unwrap();
+            assert_eq!(observations.len(), env_amount * shape.information_state);
+            for (env_rewards, &done) in rewards.iter().zip(dones.iter()) {
+                if done {
+                    finished_games += 1;
+                } else {
+                    assert_eq!(env_rewards, &[0; MAX_PLAYERS]);
+                }
+            }
+            // Finished games are reset right away
+            assert!(vec_env.envs.iter().all(|env| !env.is_done()));
+        }
+        assert!(finished_games > env_amount);
+
+        // An illegal action is rejected before any environment is stepped
+        let game_states = vec_env.envs.iter().map(|env| format!("{:?}", env.game_state)).collect::<Vec<String>>();
+        let mut action_identifiers = vec![1; env_amount];
+        action_identifiers[env_amount - 1] = 200;
+        assert!(vec_env.step(&action_identifiers).is_err());
+        assert!(vec_env.step(&action_identifiers[1..]).is_err());
+        assert_eq!(vec_env.envs.iter().map(|env| format!("{:?}", env.game_state)).collect::<Vec<String>>(), game_states);
+    }
+}
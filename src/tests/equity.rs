@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod equity_tests {
+    use hand_isomorphism_rust::deck::{card_from_string, Card};
+
+    use crate::constants::HOLE_CARD_COMBOS;
+    use crate::equity::{calculate_equity, HoleCards};
+    use crate::game_states::nlth_poker::rank::get_combo_index;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        return cards.split_whitespace().map(|card| card_from_string(card.to_string())).collect()
+    }
+
+    fn hand(hand: &str) -> HoleCards<'static> {
+        return HoleCards::Hand(cards(hand).try_into().unwrap())
+    }
+
+    #[test]
+    fn test_exact_equity_on_the_turn() {
+        // The kings only win when one of the two kings left comes on the river
+        let equity_result = calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &cards("2c 7s 9d Ts"), &[], 1000, None).unwrap();
+        assert!(equity_result.exact);
+        assert_eq!(equity_result.showdowns, 44);
+        assert!((equity_result.players[0].win - 42.0 / 44.0).abs() < 1e-9);
+        assert!((equity_result.players[1].equity - 2.0 / 44.0).abs() < 1e-9);
+
+        // With a dead king there is one left
+        let equity_result = calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &cards("2c 7s 9d Ts"), &cards("Kc"), 1000, None).unwrap();
+        assert_eq!(equity_result.showdowns, 43);
+        assert!((equity_result.players[1].win - 1.0 / 43.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ties() {
+        let equity_result = calculate_equity(&[hand("2c 3d"), hand("4s 5c"), hand("7d 8d")], &cards("Ah Kh Qh Jh Th"), &[], 1, None).unwrap();
+        for equity in equity_result.players.iter() {
+            assert_eq!((equity.win, equity.tie), (0.0, 1.0));
+            assert!((equity.equity - 1.0 / 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_range_equity_is_weighted_by_combo() {
+        let board = cards("2c 7s 9d Ts");
+        let mut range = [0.0; HOLE_CARD_COMBOS];
+        range[get_combo_index(card_from_string("Kh".to_string()), card_from_string("Kd".to_string()))] = 1.0;
+        range[get_combo_index(card_from_string("Qh".to_string()), card_from_string("Qd".to_string()))] = 3.0;
+        // Combos that share a card with the other hand can't be dealt
+        range[get_combo_index(card_from_string("Ah".to_string()), card_from_string("Qc".to_string()))] = 5.0;
+
+        let range_equity = calculate_equity(&[hand("Ah Ad"), HoleCards::Range(&range)], &board, &[], 1000, None).unwrap();
+        let kings_equity = calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &board, &[], 1000, None).unwrap();
+        let queens_equity = calculate_equity(&[hand("Ah Ad"), hand("Qh Qd")], &board, &[], 1000, None).unwrap();
+        assert!(range_equity.exact);
+        let expected_equity = (kings_equity.players[1].equity + 3.0 * queens_equity.players[1].equity) / 4.0;
+        assert!((range_equity.players[1].equity - expected_equity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_monte_carlo_equity() {
+        let players = [hand("Ah Ad"), hand("Kh Kd")];
+        let equity_result = calculate_equity(&players, &[], &[], 4000, Some(1)).unwrap();
+        assert!(!equity_result.exact);
+        assert_eq!(equity_result.showdowns, 4000);
+        // Aces are about an 82% favourite against kings
+        assert!((equity_result.players[0].equity - 0.82).abs() < 0.03);
+        assert!((equity_result.players[0].equity + equity_result.players[1].equity - 1.0).abs() < 1e-9);
+        assert_eq!(calculate_equity(&players, &[], &[], 4000, Some(1)).unwrap(), equity_result);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(calculate_equity(&[hand("Ah Ad")], &[], &[], 100, None).is_err());
+        assert!(calculate_equity(&[hand("Ah Ad"), hand("Ah Kd")], &[], &[], 100, None).is_err());
+        assert!(calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &cards("2c 7s 9d Ts"), &cards("2c"), 100, None).is_err());
+        // No samples, on the Monte Carlo and the exact path
+        assert!(calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &[], &[], 0, None).is_err());
+        assert!(calculate_equity(&[hand("Ah Ad"), hand("Kh Kd")], &cards("2c 7s 9d Ts 3h"), &[], 0, None).is_err());
+    }
+
+    #[test]
+    fn test_not_enough_cards_left() {
+        let players = [hand("Ah Ad"), hand("Kh Kd")];
+        let board = cards("2c 7s 9d");
+        // Every other card is dead except for one, while the board needs two more
+        let dead_cards = (0..52u8).filter(|card| !board.contains(card) && !cards("Ah Ad Kh Kd 3c").contains(card)).collect::<Vec<Card>>();
+        assert!(calculate_equity(&players, &board, &dead_cards, 1000, None).is_err());
+        assert!(calculate_equity(&players, &board, &dead_cards[1..], 1000, None).unwrap().exact);
+
+        // Six ranges need twelve cards, but only eleven are left after the board and the dead cards
+        let range = [1.0; HOLE_CARD_COMBOS];
+        let board = cards("2c 7s 9d Ts 3h");
+        let dead_cards = (0..52u8).filter(|card| !board.contains(card)).skip(11).collect::<Vec<Card>>();
+        assert!(calculate_equity(&[HoleCards::Range(&range); 6], &board, &dead_cards, 1000, None).is_err());
+    }
+}
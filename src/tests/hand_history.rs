@@ -0,0 +1,270 @@
+#[cfg(test)]
+mod hand_history_tests {
+    use crate::game_states::base_game_state::GameState;
+    use crate::hand_history::pokerstars::{parse_hand_histories, parse_hand_history, HandHistoryMismatch};
+
+    const THREE_PLAYER_SHOWDOWN: &str = "PokerStars Hand #1001: Tournament #5, Hold'em No Limit - Level I (50/100) - 2024/01/01 12:00:00 ET
+Table '5 1' 6-max Seat #1 is the button
+Seat 1: Alice (10000 in chips)
+Seat 2: Bob (10000 in chips)
+Seat 3: Carol (10000 in chips)
+Seat 4: Dan (10000 in chips) is sitting out
+Alice: posts the ante 10
+Bob: posts the ante 10
+Carol: posts the ante 10
+Bob: posts small blind 50
+Carol: posts big blind 100
+*** HOLE CARDS ***
+Dealt to Alice [As Ks]
+Alice: raises 200 to 300
+Bob: folds
+Carol: calls 200
+*** FLOP *** [Jd Qh Td]
+Carol: checks
+Alice: bets 400
+Carol: calls 400
+*** TURN *** [Jd Qh Td] [5s]
+Carol: checks
+Alice: checks
+*** RIVER *** [Jd Qh Td 5s] [3h]
+Carol: checks
+Alice: checks
+*** SHOW DOWN ***
+Alice: shows [As Ks] (a straight, Ten to Ace)
+Carol: shows [2c 3d] (a pair of Threes)
+Alice collected 1480 from pot
+*** SUMMARY ***
+Total pot 1480 | Rake 0
+Board [Jd Qh Td 5s 3h]
+Seat 1: Alice (button) showed [As Ks] and won (1480) with a straight, Ten to Ace
+Seat 2: Bob (small blind) folded before Flop
+Seat 3: Carol (big blind) showed [2c 3d] and lost with a pair of Threes
+";
+
+    const HEADSUP_CASH_FOLD: &str = "PokerStars Hand #2002: Hold'em No Limit ($0.50/$1.00 USD) - 2024/01/01 12:00:00 ET
+Table 'Alpha' 2-max Seat #2 is the button
+Seat 1: Dave ($100 in chips)
+Seat 2: Erin ($80.50 in chips)
+Erin: posts small blind $0.50
+Dave: posts big blind $1
+*** HOLE CARDS ***
+Erin: raises $2 to $3
+Dave: raises $6 to $9
+Erin: folds
+Uncalled bet ($6) returned to Dave
+Dave collected $6 from pot
+Dave: doesn't show hand
+*** SUMMARY ***
+Total pot $6 | Rake $0
+Seat 1: Dave (big blind) collected ($6)
+Seat 2: Erin (button) (small blind) folded before Flop
+";
+
+    #[test]
+    fn test_three_player_showdown() {
+        let hand = parse_hand_history(THREE_PLAYER_SHOWDOWN).unwrap();
+        assert_eq!(hand.hand_id, "1001");
+        // The small blind becomes player 0 and the button is last
+        assert_eq!(hand.players.iter().map(|player| player.name.as_str()).collect::<Vec<_>>(), vec!["Bob", "Carol", "Alice"]);
+        assert_eq!((hand.small_blind, hand.big_blind, hand.ante), (50, 100, 10));
+        assert_eq!(hand.states.len(), 11);
+        assert!(hand.final_state().is_terminal());
+        assert!(hand.payoffs_verified);
+        assert_eq!(hand.mismatches, vec![]);
+        assert_eq!(hand.final_state().get_payoffs(), [-60, -710, 770, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_headsup_cash_uncalled_bet() {
+        let hand = parse_hand_history(HEADSUP_CASH_FOLD).unwrap();
+        // Amounts are converted to cents
+        assert_eq!(hand.players[0].name, "Erin");
+        assert_eq!(hand.players[0].starting_stack, 8050);
+        assert_eq!(hand.players[1].returned, 600);
+        assert_eq!(hand.total_pot, 600);
+        assert_eq!(hand.mismatches, vec![]);
+        assert_eq!(hand.final_state().get_payoffs(), [-300, 300, 0, 0, 0, 0]);
+
+        // Amounts that don't fit in cents are an error
+        assert!(parse_hand_history(&HEADSUP_CASH_FOLD.replace("($100 in chips)", "($50000000 in chips)")).is_err());
+    }
+
+    #[test]
+    fn test_tournament_buy_in_is_not_in_cents() {
+        let hand = parse_hand_history(&THREE_PLAYER_SHOWDOWN.replace("Tournament #5, Hold'em", "Tournament #5, $0.98+$0.02 USD Hold'em")).unwrap();
+        assert_eq!((hand.small_blind, hand.big_blind, hand.ante), (50, 100, 10));
+        assert_eq!(hand.players[0].starting_stack, 10000);
+        assert_eq!(hand.final_state().get_payoffs(), [-60, -710, 770, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_payoff_mismatch_is_reported() {
+        let hand = parse_hand_history(&THREE_PLAYER_SHOWDOWN.replace("Alice collected 1480", "Alice collected 1400")).unwrap();
+        assert_eq!(hand.mismatches, vec![HandHistoryMismatch::Payoff { player_index: 2, logged: 690, computed: 770 }]);
+    }
+
+    #[test]
+    fn test_out_of_turn_action_is_an_error() {
+        let text = THREE_PLAYER_SHOWDOWN.replace("Alice: raises 200 to 300\nBob: folds", "Bob: folds\nAlice: raises 200 to 300");
+        assert!(parse_hand_history(&text).is_err());
+    }
+
+    #[test]
+    fn test_multiple_hands() {
+        let hands = parse_hand_histories(&format!("{}\n\n\n{}", THREE_PLAYER_SHOWDOWN, HEADSUP_CASH_FOLD));
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[0].as_ref().unwrap().hand_id, "1001");
+        assert_eq!(hands[1].as_ref().unwrap().hand_id, "2002");
+    }
+}
+
+#[cfg(test)]
+mod phh_tests {
+    use hand_isomorphism_rust::deck::card_from_string;
+
+    use crate::constants::{BOARD_CARD_AMOUNTS, NO_CARD_PLACEHOLDER};
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::hand_history::phh::{export_phh, import_phh, PHHRecord};
+    use crate::structs::{ActionType, Action, BetRounding, BetSizing, RoundingMode, RoundingUnit};
+
+    const THREE_PLAYER_PHH: &str = r#"
+variant = "NT"
+antes = [10, 10, 10]
+blinds_or_straddles = [50, 100, 0]
+min_bet = 100
+starting_stacks = [10000, 10000, 10000]
+actions = [
+  "d dh p1 ????",
+  "d dh p2 2c3d",
+  "d dh p3 AsKs",
+  "p3 cbr 300",
+  "p1 f",
+  "p2 cc",
+  "d db JdQhTd",
+  "p2 cc",
+  "p3 cbr 400 # continuation bet",
+  "p2 cc",
+  "d db 5s",
+  "p2 cc",
+  "p3 cc",
+  "d db 3h",
+  "p2 cc",
+  "p3 cc",
+  "p2 sm 2c3d",
+  "p3 sm AsKs",
+]
+"#;
+
+    fn setup_game_state(player_amount: usize) -> NLTHGameState {
+        let mut nlth_game_state = NLTHGameState::new_empty(player_amount, false, None);
+        nlth_game_state.private_hands = [
+            [card_from_string("As".to_string()), card_from_string("Ks".to_string())],
+            [card_from_string("2c".to_string()), card_from_string("3d".to_string())],
+            [card_from_string("4h".to_string()), card_from_string("5h".to_string())],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+        ];
+        nlth_game_state.community_cards = [
+            card_from_string("Jd".to_string()), card_from_string("Qh".to_string()),
+            card_from_string("Td".to_string()), card_from_string("5s".to_string()),
+            card_from_string("3h".to_string()),
+        ];
+        nlth_game_state
+    }
+
+    fn assert_same_hand(a: &NLTHGameState, b: &NLTHGameState) {
+        assert_eq!(a.round, b.round);
+        assert_eq!(a.private_hands[..a.player_amount], b.private_hands[..b.player_amount]);
+        // Only the board cards of the rounds that were reached are exported
+        let dealt_card_amount = BOARD_CARD_AMOUNTS[a.round];
+        assert_eq!(a.community_cards[..dealt_card_amount], b.community_cards[..dealt_card_amount]);
+        assert_eq!(a.stacks[..a.player_amount], b.stacks[..b.player_amount]);
+        assert_eq!(a.bets, b.bets);
+        // Imported bets are stored in chips, so only the action types have to match
+        for round in 0..a.history.len() {
+            let action_types = |game_state: &NLTHGameState| game_state.history[round].iter().map(|action| action.action_type).collect::<Vec<ActionType>>();
+            assert_eq!(action_types(a), action_types(b));
+        }
+        assert_eq!(a.folded_players, b.folded_players);
+        assert_eq!(a.get_payoffs(), b.get_payoffs());
+    }
+
+    #[test]
+    fn test_import() {
+        let states = import_phh(&PHHRecord::from_toml(THREE_PLAYER_PHH).unwrap()).unwrap();
+        let final_state = states.last().unwrap();
+        assert_eq!(states.len(), 11);
+        assert!(final_state.is_terminal());
+        assert_eq!(final_state.private_hands[2], [card_from_string("As".to_string()), card_from_string("Ks".to_string())]);
+        assert_eq!(final_state.private_hands[0], [NO_CARD_PLACEHOLDER; 2]);
+        assert_eq!(final_state.get_payoffs(), [-60, -710, 770, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_export_import_round_trip_multiplayer() {
+        let mut game_state = setup_game_state(3);
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.is_terminal());
+
+        let record = export_phh(&game_state).unwrap();
+        assert_eq!(record.actions[3], "p3 cbr 250");
+        // The board is dealt to the end before the showdown
+        assert_eq!(record.actions.iter().filter(|action| action.starts_with("d db")).count(), 3);
+
+        let imported_record = PHHRecord::from_toml(&record.to_toml()).unwrap();
+        assert_eq!(imported_record, record);
+        assert_same_hand(import_phh(&imported_record).unwrap().last().unwrap(), &game_state);
+    }
+
+    #[test]
+    fn test_export_import_round_trip_headsup() {
+        let mut game_state = setup_game_state(2);
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 75, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+
+        let record = export_phh(&game_state).unwrap();
+        // p1 is the big blind in heads-up
+        assert_eq!(record.actions[0], "d dh p1 2c3d");
+        assert_eq!(record.actions[2], "p2 cc");
+        let imported_state = import_phh(&record).unwrap().pop().unwrap();
+        assert_same_hand(&imported_state, &game_state);
+        assert_eq!(export_phh(&imported_state).unwrap().actions, record.actions);
+    }
+
+    #[test]
+    fn test_export_import_round_trip_with_bet_rounding() {
+        let mut game_state = setup_game_state(2);
+        game_state.bet_rounding = BetRounding { mode: RoundingMode::Up, unit: RoundingUnit::BigBlind };
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        // 75% of a pot of 200 is rounded up to 2 big blinds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 75, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+
+        let record = export_phh(&game_state).unwrap();
+        assert!(record.actions.contains(&"p1 cbr 200".to_string()));
+        assert_same_hand(import_phh(&record).unwrap().last().unwrap(), &game_state);
+    }
+
+    #[test]
+    fn test_export_rejects_translated_bets() {
+        let game_state = setup_game_state(2);
+        // The history says pot sized, but 333 chips went in
+        let translated_state = game_state.handle_translated_bet(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction }, 333);
+        assert!(export_phh(&translated_state).is_err());
+        assert!(export_phh(&translated_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction })).is_err());
+        // Exact chip amounts replay to the same bets
+        assert!(export_phh(&game_state.handle_bet_amount(333)).is_ok());
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod hand_index_tests {
+    use hand_isomorphism_rust::deck::card_from_string;
+
+    use crate::constants::BOARD_CARD_AMOUNTS;
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::game_states::nlth_poker::hand_index::{get_hand_index, get_hand_index_amount};
+    use crate::structs::{Action, ActionType, BetSizing};
+
+    fn cards(hand: &str) -> Vec<u8> {
+        return hand.split_whitespace().map(|card| card_from_string(card.to_string())).collect()
+    }
+
+    #[test]
+    fn test_hand_index() {
+        let game_state_with_cards = |hand: &str, board: &str| {
+            let mut game_state = NLTHGameState::new_empty(2, false, None);
+            game_state.private_hands[0] = cards(hand).try_into().unwrap();
+            game_state.private_hands[1] = cards("3c 3d").try_into().unwrap();
+            game_state.community_cards = cards(board).try_into().unwrap();
+            return game_state
+        };
+        // The same hand with spades and hearts and diamonds and clubs swapped
+        let mut game_state = game_state_with_cards("As Kd", "2s 7h 9c Jd Qh");
+        let mut isomorphic_game_state = game_state_with_cards("Ah Kc", "2h 7s 9d Jc Qs");
+        let mut other_game_state = game_state_with_cards("As Ks", "2s 7h 9c Jd Qh");
+
+        for (round, &board_card_amount) in BOARD_CARD_AMOUNTS.iter().enumerate() {
+            let hand_index = game_state.get_hand_index(0).unwrap();
+            assert_eq!(isomorphic_game_state.get_hand_index(0).unwrap(), hand_index);
+            assert_ne!(other_game_state.get_hand_index(0).unwrap(), hand_index);
+            assert!(hand_index < get_hand_index_amount(round));
+
+            // The cards of an index are a hand with that index
+            let (hole_cards, board) = NLTHGameState::get_hand_from_index(round, hand_index).unwrap();
+            assert_eq!(board.len(), board_card_amount);
+            let mut unindexed_game_state = game_state.clone();
+            unindexed_game_state.private_hands[0] = hole_cards;
+            unindexed_game_state.community_cards[..board.len()].copy_from_slice(&board);
+            assert_eq!(unindexed_game_state.get_hand_index(0).unwrap(), hand_index);
+
+            for state in [&mut game_state, &mut isomorphic_game_state, &mut other_game_state] {
+                *state = state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+                *state = state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+            }
+        }
+
+        assert!(NLTHGameState::new_empty(2, false, None).get_hand_index(0).is_err());
+        assert!(NLTHGameState::get_hand_from_index(4, 0).is_err());
+        assert!(get_hand_index(1, &cards("As Kd 2s 7h")).is_err());
+        assert!(get_hand_index(4, &cards("As Kd")).is_err());
+    }
+}
@@ -4,11 +4,12 @@
 mod poker_tests_headsup {
     use cfr_game_states::constants::NO_CARD_PLACEHOLDER;
     use hand_isomorphism_rust::deck::card_from_string;
+    use smallvec::smallvec;
     
     use crate::game_states::base_game_state::GameState;
-    use crate::game_states::nlth_poker::game_state::NLTHGameState;
-    use crate::structs::{ActionType, Action};
-    use crate::tests::action_abstraction::AVAILABLE_ACTIONS;
+    use crate::game_states::nlth_poker::game_state::{resolve_bet_amount, NLTHGameState};
+    use crate::structs::{ActionType, Action, BetRounding, BetSizing, RoundingMode, RoundingUnit};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
 
     // Helper function to create a standardized game state
     fn setup_game_state() -> NLTHGameState {
@@ -30,17 +31,105 @@ mod poker_tests_headsup {
         nlth_game_state
     }
 
+    #[test]
+    fn bet_sizing_test_case() {
+        let mut game_state = setup_game_state();
+        let big_blinds = Action { action_type: ActionType::Bet, raise_amount: 250, sizing: BetSizing::BigBlinds };
+        let last_raise = Action { action_type: ActionType::Bet, raise_amount: 300, sizing: BetSizing::LastRaise };
+        let chips = Action { action_type: ActionType::Bet, raise_amount: 2000, sizing: BetSizing::Chips };
+        // Every sizing is the amount the player raises to, so the small blind only adds 200 for 2.5bb
+        assert_eq!(resolve_bet_amount(&game_state, &big_blinds), 200);
+        game_state = game_state.handle_action(big_blinds);
+        assert_eq!(game_state.bets[0], [250, 100, 0, 0, 0, 0]);
+        assert_eq!(resolve_bet_amount(&game_state, &last_raise), 650);
+        game_state = game_state.handle_action(last_raise);
+        assert_eq!(game_state.bets[0], [250, 750, 0, 0, 0, 0]);
+        assert_eq!(game_state.minimum_raise_amount, 500);
+        game_state = game_state.handle_action(chips);
+        assert_eq!(game_state.bets[0], [2000, 750, 0, 0, 0, 0]);
+        assert_eq!(game_state.stacks[0], 8000);
+        assert_eq!(chips.as_string(), "Bet 2000c");
+
+        // There's no bet to raise on the flop yet, and 0.5bb is less than the minimum raise
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        let small_bet = Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::BigBlinds };
+        let flop_actions = game_state.get_active_player_actions(Some(&smallvec![last_raise, big_blinds, small_bet]));
+        assert!(!flop_actions.contains(&last_raise) && !flop_actions.contains(&small_bet));
+        assert!(flop_actions.contains(&big_blinds));
+
+        // Exact amounts are stored in chips
+        let game_state = setup_game_state().handle_bet_amount(200);
+        assert_eq!(game_state.history[0].last(), Some(&Action { action_type: ActionType::Raise, raise_amount: 250, sizing: BetSizing::Chips }));
+    }
+
+    #[test]
+    fn bet_rounding_test_case() {
+        let mut game_state = setup_game_state();
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        // A third of the 200 chip pot
+        let bet = Action { action_type: ActionType::Bet, raise_amount: 33, sizing: BetSizing::PotFraction };
+        let resolve = |mode: RoundingMode, unit: RoundingUnit| {
+            let mut game_state = game_state.clone();
+            game_state.bet_rounding = BetRounding { mode, unit };
+            return resolve_bet_amount(&game_state, &bet)
+        };
+        assert_eq!(resolve(RoundingMode::Down, RoundingUnit::Chips(1)), 66);
+        assert_eq!(resolve(RoundingMode::Nearest, RoundingUnit::Chips(5)), 65);
+        assert_eq!(resolve(RoundingMode::Up, RoundingUnit::Chips(5)), 70);
+        assert_eq!(resolve(RoundingMode::Nearest, RoundingUnit::BigBlind), 100);
+        assert_eq!(resolve(RoundingMode::Down, RoundingUnit::BigBlind), 0);
+
+        // The legal actions and the applied bet use the same amount, 110 chips rounded up to 125
+        let bet = Action { action_type: ActionType::Bet, raise_amount: 55, sizing: BetSizing::PotFraction };
+        game_state.bet_rounding = BetRounding { mode: RoundingMode::Up, unit: RoundingUnit::Chips(25) };
+        assert!(game_state.get_active_player_actions(Some(&smallvec![bet])).contains(&bet));
+        assert_eq!(game_state.handle_action(bet).bets[1], [0, 125, 0, 0, 0, 0]);
+        assert_eq!(BetRounding::default().round(1999, 100, 100), 19);
+    }
+
+    #[test]
+    fn check_raise_test_case() {
+        let action = |action_type: ActionType, raise_amount: u16| Action { action_type, raise_amount, sizing: BetSizing::PotFraction };
+        let mut game_state = setup_game_state();
+        // Completing the small blind is a call and the big blind's option a check
+        game_state = game_state.handle_action(action(ActionType::Call, 0));
+        game_state = game_state.handle_action(action(ActionType::Call, 0));
+        assert_eq!(game_state.history[0].as_slice(), &[action(ActionType::Call, 0), action(ActionType::Check, 0)]);
+        assert_eq!(game_state.round, 1);
+
+        // Check and Raise can be passed as well, the stored type follows from the state
+        game_state = game_state.handle_action(action(ActionType::Check, 0));
+        game_state = game_state.handle_action(action(ActionType::Raise, 50));
+        game_state = game_state.handle_action(action(ActionType::Bet, 100));
+        assert_eq!(game_state.history[1].as_slice(), &[action(ActionType::Check, 0), action(ActionType::Bet, 50), action(ActionType::Raise, 100)]);
+        assert_eq!(game_state.get_current_bet_count(), 2);
+        assert!(!game_state.all_remaining_players_checked());
+        game_state = game_state.handle_action(action(ActionType::Check, 0));
+        assert_eq!(game_state.history[1].last(), Some(&action(ActionType::Call, 0)));
+        assert_eq!(game_state.round, 2);
+
+        game_state = game_state.handle_action(action(ActionType::Check, 0));
+        game_state = game_state.handle_action(action(ActionType::Call, 0));
+        assert!(game_state.history[2].iter().all(|action| action.action_type == ActionType::Check));
+        assert_eq!(game_state.round, 3);
+
+        // Checks and raises share the ActionIdentifier of calls and bets
+        assert_eq!(action(ActionType::Check, 0).into_identifier(), action(ActionType::Call, 0).into_identifier());
+        assert_eq!(DEFAULT_ACTION_ABSTRACTION.get_identifier(&action(ActionType::Raise, 100)), Some(8));
+    }
+
     #[test]
     fn minimum_raise_amount_test_case() {
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.minimum_raise_amount, 200);
 
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.minimum_raise_amount, 200);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.minimum_raise_amount, 300);
     }
 
@@ -49,38 +138,38 @@ mod poker_tests_headsup {
         let mut game_state = setup_game_state();
         assert_eq!(game_state.bets[0], [50, 100, 0, 0, 0, 0]);
         println!("actions: {:?}", game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         ));
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         )[3].raise_amount, 100);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.bets[0], [200, 100, 0, 0, 0, 0]);
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         )[3].raise_amount, 75);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 75 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 75, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.bets[0], [200, 300, 0, 0, 0, 0]);
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         )[3].raise_amount, 75);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.bets[0], [600, 300, 0, 0, 0, 0]);
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         )[3].raise_amount, 100);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.bets[0], [600, 600, 0, 0, 0, 0]);
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         )[3].raise_amount, 100);
     }
 
     #[test]
     fn test_pre_flop_folding() {
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 2500 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 2500, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), true);
         let payoffs = game_state.get_payoffs();
         assert_eq!(payoffs[1] < 0, true); // Assuming player 2 is the one who folded
@@ -89,51 +178,51 @@ mod poker_tests_headsup {
     #[test]
     fn test_pre_flop_calling() {
         let mut game_state = setup_game_state();
-        let available_actions = AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count());
-        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::Call, raise_amount: 0 }));
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 1 calls
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 2 calls
+        let available_actions = DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state);
+        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 calls
         assert_eq!(game_state.is_terminal(), false); // Should not be terminal yet
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Move to next round
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Move to next round
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), false);
     }
 
     #[test]
     fn test_preflop_raise_reraise_and_call() {
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 }); // Player A raises
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 400 }); // Player B re-raises (3-bet)
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player A calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction }); // Player A raises
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 400, sizing: BetSizing::PotFraction }); // Player B re-raises (3-bet)
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player A calls
         assert_eq!(game_state.is_terminal(), false);
     }
 
     #[test]
     fn test_preflop_raise_allin_and_fold() {
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 }); // Player A raises
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 }); // Player B goes all-in
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction }); // Player A raises
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player B goes all-in
         assert_eq!(game_state.is_terminal(), false);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player A folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player A folds
         assert_eq!(game_state.is_terminal(), true);
     }
 
     #[test]
     fn test_preflop_raise_allin_and_call() {
         let mut game_state = setup_game_state();
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 }); // Player A raises
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 }); // Player B goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 }); // Player A calls with their own all-in
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction }); // Player A raises
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player B goes all-in
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player A calls with their own all-in
         assert_eq!(game_state.is_terminal(), true);
     }
 
     #[test]
     fn test_all_in_and_fold() {
         let mut game_state = setup_game_state();
-        let available_actions = AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count());
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
-        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::Fold, raise_amount: 0 }));
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        let available_actions = DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state);
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), true);
         let payoffs = game_state.get_payoffs();
         assert_eq!(payoffs[0] > 0, true);
@@ -142,10 +231,10 @@ mod poker_tests_headsup {
     #[test]
     fn test_all_in_and_call() {
         let mut game_state = setup_game_state();
-        let available_actions = AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count());
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
-        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::AllIn, raise_amount: 0 }));
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        let available_actions = DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state);
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.get_active_player_actions(available_actions).contains(&Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction }));
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), true);
         let payoffs = game_state.get_payoffs();
         assert_eq!(payoffs[0], 10_000);
@@ -156,17 +245,17 @@ mod poker_tests_headsup {
     fn test_multiple_betting_rounds() {
         let mut game_state = setup_game_state();
         // Initial bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Flop bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Turn bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // River bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), true);
         assert!(game_state.get_payoffs().iter().any(|&x| x > 0));
     }
@@ -179,30 +268,30 @@ mod poker_tests_headsup {
         // Player 1 acts first after blinds, betting 1.5x the current pot.
         // Also account for the call of the big blind, so 50 will be added to the pot apart from the bet
         let bet_size = (1.5 * (initial_pot as f64 + 50.0)) as u32;
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[0], 9900 /* 9950 minus 50 for the call */ - bet_size);
         assert_eq!(game_state.get_total_pot(), initial_pot + 50 + bet_size);
 
         // Player 2 responds with a call
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[1], 9900 - bet_size);
         assert_eq!(game_state.get_total_pot(), initial_pot + 50 + 2 * bet_size);
 
         // Moving to the next betting round, Player 2 is FTA and decides to bet 1x the new pot size
         let new_pot = game_state.get_total_pot();
         let new_bet_size = new_pot; // 1x the pot
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[1], 9900 - bet_size - new_bet_size);
         assert_eq!(game_state.get_total_pot(), new_pot + new_bet_size);
 
         // Player 1 goes all-in, which is less than a normal Bet1 due to previous betting
         let all_in_amount = game_state.stacks[0]; // All remaining stack
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[0], 0);
         assert_eq!(game_state.get_total_pot(), new_pot + new_bet_size + all_in_amount);
 
         // Player 2 also goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.get_total_pot(), 20000);
 
         // Verify game ends
@@ -215,34 +304,34 @@ mod poker_tests_headsup {
         // Preflop
         // SB (player 0) starts
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });  // SB calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });  // SB calls
         // BB (player 1) acts
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // BB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // BB checks
 
         // Move to flop
         // Postflop, BB (player 1) starts
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // BB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // BB checks
         // SB (player 0) acts
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // SB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // SB checks
 
         // Move to turn
         // Turn, BB (player 1) starts
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // BB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // BB checks
         // SB (player 0) acts
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // SB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // SB checks
 
         // Move to river
         // River, BB (player 1) starts
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // BB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // BB checks
         // SB (player 0) acts
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // SB checks
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // SB checks
 
         assert_eq!(game_state.is_terminal(), true); // Game should be terminal
     }
@@ -251,17 +340,17 @@ mod poker_tests_headsup {
     fn test_payoffs() {
         let mut game_state = setup_game_state();
         // Initial bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Flop bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Turn bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         // River bets
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.is_terminal(), true);
 
         assert_eq!(game_state.get_payoffs(), [7200, -7200, 0, 0, 0, 0]);
@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod poker_tests_multiplayer {
     use hand_isomorphism_rust::deck::card_from_string;
+    use smallvec::smallvec;
     
     use crate::game_states::base_game_state::GameState;
     use crate::game_states::nlth_poker::game_state::NLTHGameState;
-    use crate::structs::{ActionType, Action};
-    use crate::tests::action_abstraction::AVAILABLE_ACTIONS;
+    use crate::structs::{ActionType, Action, BetSizing};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
 
     // Helper function to create a standardized game state with six players
     fn setup_game_state_six_players() -> NLTHGameState {
@@ -36,18 +37,18 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 5 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 5 calls
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 6 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 calls
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 1 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 calls
         assert_eq!(game_state.active_player_index, 1);
         assert_eq!(game_state.round, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 2 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 calls
         assert_eq!(game_state.round, 1);
         assert_eq!(game_state.active_player_index, 0);
         assert_eq!(game_state.is_terminal(), false);
@@ -58,30 +59,30 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 5 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 5 calls
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 6 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 calls
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 }); // Player 1 bets 1x pot
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction }); // Player 1 bets 1x pot
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 2 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 calls
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50 }); // Player 4 bets 0.5x pot
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction }); // Player 4 bets 0.5x pot
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 5 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 5 calls
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 6 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 folds
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 1 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 calls
         assert_eq!(game_state.active_player_index, 2);
         assert_eq!(game_state.round, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls (skip player 2 cuz he folded)
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls (skip player 2 cuz he folded)
         assert_eq!(game_state.round, 1);
         assert_eq!(game_state.active_player_index, 0);
         assert_eq!(game_state.is_terminal(), false);
@@ -96,18 +97,18 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 5 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 5 calls
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 6 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 calls
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 1 bets 1x pot
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 bets 1x pot
         assert_eq!(game_state.active_player_index, 1);
         assert_eq!(game_state.round, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 2 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 calls
         assert_eq!(game_state.round, 1);
         assert_eq!(game_state.active_player_index, 0);
         assert_eq!(game_state.is_terminal(), false);
@@ -118,17 +119,17 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150 }); // Player 3 bets
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150, sizing: BetSizing::PotFraction }); // Player 3 bets
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 4 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 folds
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 }); // Player 5 bets
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction }); // Player 5 bets
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 6 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 calls
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 1 bets 1x pot
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 bets 1x pot
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 2 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 calls
         assert_eq!(game_state.round, 0);
         assert_eq!(game_state.all_remaining_players_checked(), false);
         assert_eq!(game_state.active_player_index, 2);
@@ -140,23 +141,23 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 3 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
         assert_eq!(game_state.active_player_index, 3);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.active_player_index, 4);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150 }); // Player 5 bets
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 150, sizing: BetSizing::PotFraction }); // Player 5 bets
         assert_eq!(game_state.active_player_index, 5);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 6 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 6 calls
         assert_eq!(game_state.active_player_index, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 1 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 folds
         assert_eq!(game_state.active_player_index, 1);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 2 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 folds
         assert_eq!(game_state.active_player_index, 2);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 3 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 folds
         assert_eq!(game_state.active_player_index, 3);
         assert_eq!(game_state.round, 0);
         assert_eq!(game_state.all_remaining_players_checked(), false);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.round, 1);
         assert_eq!(game_state.active_player_amount, 3); // Players 4, 5 and 6 remain
         assert_eq!(game_state.folded_players, [true, true, true, false, false, false]);
@@ -171,15 +172,15 @@ mod poker_tests_multiplayer {
     fn test_initial_folds() {
         let mut game_state = setup_game_state_six_players();
         // Simulate several players folding
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 0 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 1 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 2 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 }); // Player 3 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 0 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 1 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 2 folds
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 folds
         // Two players remaining should continue the game
         assert_eq!(game_state.is_terminal(), false);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 4 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 4 calls
         assert_eq!(game_state.round, 0);
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 }); // Player 5 calls
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 5 calls
         // Game should now proceed to post-flop
         assert_eq!(game_state.round, 1);
         assert_eq!(game_state.is_terminal(), false);
@@ -193,10 +194,10 @@ mod poker_tests_multiplayer {
     fn test_minimum_raise_requirement() {
         let mut game_state = setup_game_state_six_players();
         // Player 2 raises the minimum amount
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 200, sizing: BetSizing::PotFraction });
 
         let actions = game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         );
         let contains_illegal_action = actions.iter().find(|action| action.raise_amount > 0 && action.raise_amount < 79);
         assert!(contains_illegal_action.is_none());
@@ -208,25 +209,23 @@ mod poker_tests_multiplayer {
         game_state.stacks[3] = 180; // Player 3 has only 180 left
 
         // Player 2 raises 300
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 300 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 300, sizing: BetSizing::PotFraction });
 
         // Player 3 goes all-in with less than the minimum raise
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
-        assert_eq!(game_state.all_in_players[3], 0); // Ensure that player 3 is marked as all-in
-        assert!(game_state.current_pot > 0); // Ensure that a new pot is possibly created if needed
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.all_in_players[3]); // Ensure that player 3 is marked as all-in
 
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
 
-        // The first pots should not go above 180
-        for bet in game_state.pots[0] {
-            assert!(bet <= 180);
-        }
+        // The main pot should not go above 180 per player
+        assert_eq!(game_state.get_contributions(), [50, 100, 750, 180, 750, 2580]);
+        let pots = game_state.get_pot_manager().pots();
+        assert_eq!(pots.len(), 2);
+        assert_eq!((pots[0].amount, pots[0].eligible_players.as_slice()), (50 + 100 + 4 * 180, &[0, 1, 2, 3, 4, 5][..]));
 
         // Bets should be distributed properly
-        assert_eq!(game_state.pots[1][2], 570);
-        assert_eq!(game_state.pots[1][4], 570);
-        assert_eq!(game_state.pots[1][5], 2400);
+        assert_eq!((pots[1].amount, pots[1].eligible_players.as_slice()), (570 + 570 + 2400, &[0, 1, 2, 4, 5][..]));
     }
 
     #[test]
@@ -234,29 +233,29 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
 
         // Player 2 calls
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Player 3 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Player 4 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Player 5 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Player 0 calls
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Player 1 raises
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
 
         // Check that we're still in round 0
         assert_eq!(game_state.round, 0);
         assert_eq!(game_state.active_player_index, 2);
 
         // Player 2 calls
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         assert_eq!(game_state.active_player_index, 0);
     }
@@ -274,12 +273,12 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Simulating bets and calls to reach showdown
         for _ in 0..6 {
-            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         }
         // Assuming all players check on the flop, turn and river
         for _round in 1..4 {
             for _ in 0..6 {
-                game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+                game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
             }
         }
         assert!(game_state.is_terminal());
@@ -298,7 +297,7 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
         // Players folding, leaving one player
         for _ in 1..6 {
-            game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         }
         // Ensure the game is terminal when only one player is left
         assert!(game_state.is_terminal());
@@ -319,30 +318,30 @@ mod poker_tests_multiplayer {
         let call_amount = game_state.get_call_amount();
 
         // UTG player (index 2) decides to fold due to a large bet from the next player
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert!(game_state.folded_players[2]);
 
         // Now the action is on player 3 who decides to go all-in
         let all_in_bet = game_state.stacks[3];
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[3], 0);
         assert_eq!(game_state.get_total_pot(), initial_pot + all_in_bet);
 
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         ).len(), 2); // There should only be 2 actions - Fold or All-in
 
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[4], 10_000 - all_in_bet);
         assert_eq!(game_state.get_total_pot(), initial_pot + 2 * all_in_bet);
 
         assert_eq!(game_state.get_active_player_actions(
-            AVAILABLE_ACTIONS[game_state.get_current_round_index()].get(game_state.get_current_bet_count())
+            DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
         ).len(), 2); // There should only be 2 actions - Fold or All-in
 
         // Now player 5 acts with a minimum raise
         let min_raise = game_state.minimum_raise_amount;
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[5], 10000 - min_raise - call_amount);
         assert_eq!(game_state.get_total_pot(), initial_pot + 2 * all_in_bet + min_raise + call_amount);
 
@@ -366,12 +365,12 @@ mod poker_tests_multiplayer {
         assert_eq!(game_state.get_total_pot(), initial_pot);
 
         // Player 2 decides to call the big blind
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[2], 9900); // Called the big blind, matching player 1's bet
         assert_eq!(game_state.get_total_pot(), initial_pot + 100); // Player 2 matched the big blind
 
         // Player 3 raises, increasing the action
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[3], 9650); // Player 3's stack after raising
         assert_eq!(game_state.get_total_pot(), initial_pot + 100 + 350); // Includes original blinds, player 2's call, and player 3's raise
     }
@@ -390,22 +389,23 @@ mod poker_tests_multiplayer {
         game_state.stacks[5] = 6000;
 
         // Player 2 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 3 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 4 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 5 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Check the pots and player stacks
         assert_eq!(game_state.stacks[2], 0);
         assert_eq!(game_state.stacks[4], 0);
         assert_eq!(game_state.stacks[5], 0);
-        assert_eq!(game_state.pots[0], [50, 100, 2000, 0, 2000, 2000]); // Main pot
-        assert_eq!(game_state.pots[1], [0, 0, 0, 0, 1000, 1000]); // Side pot 1
-        assert_eq!(game_state.pots[2], [0, 0, 0, 0, 0, 3000]); // Side pot 2
-        assert_eq!(game_state.current_pot, 3); // 3 pots were used, we're now at pot 4
+        let pots = game_state.get_pot_manager().pots();
+        assert_eq!(pots.len(), 3);
+        assert_eq!((pots[0].amount, pots[0].eligible_players.as_slice()), (50 + 100 + 3 * 2000, &[0, 1, 2, 4, 5][..])); // Main pot
+        assert_eq!((pots[1].amount, pots[1].eligible_players.as_slice()), (2 * 1000, &[0, 1, 4, 5][..])); // Side pot 1
+        assert_eq!((pots[2].amount, pots[2].eligible_players.as_slice()), (3000, &[0, 1, 5][..])); // Side pot 2
     }
 
     #[test]
@@ -417,20 +417,21 @@ mod poker_tests_multiplayer {
         game_state.stacks[5] = 6000;
 
         // Player 3 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 4 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 5 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 6 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Check the pots and player stacks
         assert_eq!(game_state.stacks[4], 0);
         assert_eq!(game_state.stacks[5], 0);
-        assert_eq!(game_state.pots[0], [50, 100, 0, 0, 4000, 4000]); // Main pot
-        assert_eq!(game_state.pots[1], [0, 0, 0, 0, 0, 2000]); // Side pot 1
-        assert_eq!(game_state.current_pot, 2); // 2 pots in total
+        let pots = game_state.get_pot_manager().pots();
+        assert_eq!(pots.len(), 2);
+        assert_eq!((pots[0].amount, pots[0].eligible_players.as_slice()), (50 + 100 + 2 * 4000, &[0, 1, 4, 5][..])); // Main pot
+        assert_eq!((pots[1].amount, pots[1].eligible_players.as_slice()), (2000, &[0, 1, 5][..])); // Side pot 1
     }
 
     #[test]
@@ -442,19 +443,20 @@ mod poker_tests_multiplayer {
         game_state.stacks[5] = 4000;
         
         // Player 2 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 3 goes all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 4 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 5 matches the all-in exactly
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         // Check the pots and player stacks
         assert_eq!(game_state.stacks[3], 0);
         assert_eq!(game_state.stacks[5], 0);
-        assert_eq!(game_state.pots[0], [50, 100, 0, 4000, 0, 4000]); // Only the main pot
-        assert_eq!(game_state.current_pot, 2); // 1 pot in total
+        let pots = game_state.get_pot_manager().pots();
+        assert_eq!(pots.len(), 1); // Only the main pot
+        assert_eq!((pots[0].amount, pots[0].eligible_players.as_slice()), (50 + 100 + 2 * 4000, &[0, 1, 3, 5][..]));
     }
 
     /*
@@ -465,15 +467,15 @@ mod poker_tests_multiplayer {
         let mut game_state = setup_game_state_six_players();
 
         // Player 2 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 3 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 4 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 5 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
         // Player 0 folds
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
 
         assert!(game_state.is_terminal());
 
@@ -490,22 +492,86 @@ mod poker_tests_multiplayer {
 
         // Players index 2 to 5 check
         for _ in 2..6 {
-            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         }
 
         // Small blind all-in
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[0], 0);
 
         // Big blind calls
-        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[1], 0);
 
         // Another player raises
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
         assert_eq!(game_state.stacks[2], 10000 - 3_550); // This player's new stack after raising
     }
 
+    /*
+        //  Reopening the Betting  \\
+    */
+    fn raise_to(chips: u16) -> Action {
+        return Action { action_type: ActionType::Bet, raise_amount: chips, sizing: BetSizing::Chips }
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen_betting() {
+        let mut game_state = NLTHGameState::new_with_table(3, [400, 10000, 10000, 0, 0, 0], 50, 100, 0);
+        // Player 2 raises to 300, the small blind goes all-in for 400 which is less than a full raise
+        game_state = game_state.handle_bet_amount(300);
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(game_state.minimum_raise_amount, 200);
+
+        // The big blind hasn't acted yet, so they can still raise by at least the last full raise
+        assert!(game_state.can_raise());
+        let actions = game_state.get_active_player_actions(Some(&smallvec![raise_to(550), raise_to(600)]));
+        assert!(!actions.contains(&raise_to(550)) && actions.contains(&raise_to(600)));
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+
+        // Player 2 already acted and only faces 100 more, so they can only call or fold
+        assert!(!game_state.can_raise());
+        assert_eq!(DEFAULT_ACTION_ABSTRACTION.get_active_player_actions(&game_state).as_slice(), &[
+            Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction },
+            Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction },
+        ]);
+
+        // The next round is open to everyone again
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(game_state.round, 1);
+        assert!(game_state.can_raise());
+    }
+
+    #[test]
+    fn test_short_all_ins_add_up_to_a_full_raise() {
+        let mut game_state = NLTHGameState::new_with_table(4, [400, 550, 10000, 10000, 0, 0], 50, 100, 0);
+        game_state = game_state.handle_bet_amount(300); // Player 2 raises to 300
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction }); // Player 3 calls
+        // All-ins to 400 and 550 are both less than a full raise of 200
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(game_state.minimum_raise_amount, 200);
+
+        // Together they raise player 2 by 250, which reopens the betting
+        assert_eq!(game_state.active_player_index, 2);
+        assert!(game_state.can_raise());
+        let actions = game_state.get_active_player_actions(Some(&smallvec![raise_to(700), raise_to(750)]));
+        assert!(!actions.contains(&raise_to(700)) && actions.contains(&raise_to(750)));
+        assert!(actions.iter().any(|action| action.action_type == ActionType::AllIn));
+    }
+
+    #[test]
+    fn test_full_raise_all_in_reopens_betting() {
+        let mut game_state = NLTHGameState::new_with_table(3, [500, 10000, 10000, 0, 0, 0], 50, 100, 0);
+        game_state = game_state.handle_bet_amount(300);
+        // An all-in to 500 is exactly a full raise
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert_eq!(game_state.active_player_index, 2);
+        assert!(game_state.can_raise());
+        assert!(game_state.get_active_player_actions(Some(&smallvec![raise_to(700)])).contains(&raise_to(700)));
+    }
+
     /*
         //  Payoffs  \\
     */
@@ -514,14 +580,14 @@ mod poker_tests_multiplayer {
     fn test_payoffs() {
         let mut game_state = setup_game_state_six_players();
 
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 100 });
-        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 100 });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 100, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 100, sizing: BetSizing::PotFraction });
 
         while !game_state.is_terminal() {
-            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0 });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
         }
 
         assert_eq!(game_state.get_payoffs(), [-50, -350, -350, 0, -350, 1100]); // This player's new stack after raising
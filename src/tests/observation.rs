@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod observation_tests {
+    use hand_isomorphism_rust::deck::card_from_string;
+    use rand::prelude::*;
+    use smallvec::SmallVec;
+
+    use crate::constants::BOARD_CARD_AMOUNTS;
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::leduc_poker::game_state::LPGameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::game_states::observation::ObservationTensors;
+    use crate::structs::{ActionType, Action, BetSizing};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+
+    // Plays random games and checks the tensor sizes of every player at every step
+    fn assert_shapes<T, F>(player_amount: usize, bets_in_abstraction: F)
+    where
+        T: GameState + ObservationTensors,
+        F: Fn(&T) -> Option<&SmallVec<[Action; 40]>>,
+    {
+        let shape = T::tensor_shape();
+        let mut rng = StdRng::seed_from_u64(3);
+        for seed in 0..20 {
+            let mut game_state = T::new_empty(player_amount, true, Some(seed));
+            loop {
+                for player_index in 0..player_amount {
+                    assert_eq!(game_state.observation_tensor(player_index).len(), shape.observation);
+                    assert_eq!(game_state.information_state_tensor(player_index).len(), shape.information_state);
+                }
+                if game_state.is_terminal() {
+                    break
+                }
+                let actions = game_state.get_active_player_actions(bets_in_abstraction(&game_state));
+                game_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tensor_shapes() {
+        for player_amount in [2, 3, 6] {
+            assert_shapes::<NLTHGameState, _>(player_amount, |game_state| {
+                DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(game_state)
+            });
+        }
+        assert_shapes::<LPGameState, _>(2, |_| None);
+        assert_shapes::<KPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_kuhn_tensors() {
+        let mut game_state = KPGameState::new_empty(2, false, None);
+        game_state.private_hands[0][0] = card_from_string("Kh".to_string());
+        game_state.private_hands[1][0] = card_from_string("Jh".to_string());
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 0, sizing: BetSizing::PotFraction });
+
+        assert_eq!(game_state.observation_tensor(1), vec![0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 2.0]);
+        assert_eq!(
+            game_state.information_state_tensor(0),
+            vec![1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_opponent_cards_are_hidden() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for seed in 0..20 {
+            let mut game_state = NLTHGameState::new_empty(3, true, Some(seed));
+            while !game_state.is_terminal() {
+                let actions = game_state.get_active_player_actions(
+                    DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state)
+                );
+                game_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+
+                // Swapping the cards of the other players, including the undealt board, must not change what player 0 sees
+                let mut other_cards = game_state.clone();
+                other_cards.private_hands.swap(1, 2);
+                let dealt_card_amount = BOARD_CARD_AMOUNTS[game_state.round];
+                other_cards.community_cards[dealt_card_amount..].reverse();
+                assert_eq!(other_cards.observation_tensor(0), game_state.observation_tensor(0));
+                assert_eq!(other_cards.information_state_tensor(0), game_state.information_state_tensor(0));
+            }
+        }
+
+        let mut game_state = LPGameState::new_empty(2, true, Some(1));
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        let mut other_cards = game_state.clone();
+        other_cards.private_hands[1][0] = game_state.community_cards[0];
+        other_cards.community_cards[0] = game_state.private_hands[1][0];
+        assert_eq!(other_cards.observation_tensor(0), game_state.observation_tensor(0));
+        assert_ne!(other_cards.observation_tensor(1), game_state.observation_tensor(1));
+    }
+}
@@ -0,0 +1,445 @@
+#[cfg(test)]
+mod payoffs_tests {
+    use hand_isomorphism_rust::deck::card_from_string;
+    use rand::prelude::*;
+    use smallvec::SmallVec;
+
+    use crate::constants::{BOARD_CARD_AMOUNTS, COMMUNITY_CARD_AMOUNT, HOLE_CARD_COMBOS, NO_CARD_PLACEHOLDER};
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::leduc_poker::game_state::LPGameState;
+    use crate::game_states::nlth_poker::game_state::{NLTHGameState, OddChipRule};
+    use crate::game_states::nlth_poker::pot_manager::{Pot, PotManager};
+    use crate::game_states::nlth_poker::range_payoffs::{fold_payoffs, showdown_payoffs};
+    use crate::game_states::nlth_poker::rank::{get_combo_index, get_hole_card_combo, rank_hand, HandCategory};
+    use crate::game_states::showdown::Showdown;
+    use crate::structs::{ActionType, Action, BetSizing};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+    use crate::equity::{calculate_equity, HoleCards};
+
+    fn assert_zero_sum<T, F>(player_amount: usize, bets_in_abstraction: F)
+    where
+        T: GameState,
+        F: Fn(&T) -> Option<&SmallVec<[Action; 40]>>,
+    {
+        let mut rng = StdRng::seed_from_u64(5);
+        for seed in 0..200 {
+            let mut game_state = T::new_empty(player_amount, true, Some(seed));
+            while !game_state.is_terminal() {
+                let actions = game_state.get_active_player_actions(bets_in_abstraction(&game_state));
+                game_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+            }
+            assert_eq!(game_state.get_payoffs().iter().sum::<i32>(), 0);
+        }
+    }
+
+    #[test]
+    fn test_payoffs_are_zero_sum() {
+        for player_amount in 2..=6 {
+            assert_zero_sum::<NLTHGameState, _>(player_amount, |game_state| DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(game_state));
+        }
+        assert_zero_sum::<LPGameState, _>(2, |_| None);
+        assert_zero_sum::<KPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_split_pot_showdown_result() {
+        let game_state = setup_split_pot();
+        let showdown_result = game_state.get_showdown_result();
+        assert_eq!(showdown_result.pots.len(), 1);
+        assert_eq!(showdown_result.pots[0].amount, 251);
+        assert_eq!(showdown_result.pots[0].eligible_players.as_slice(), &[1, 2]);
+        assert_eq!(showdown_result.pots[0].winners.as_slice(), &[(1, 126), (2, 125)]);
+        // The player that folded doesn't show their hand
+        assert!(showdown_result.get_hand(0).is_none());
+        assert_eq!(showdown_result.get_hand(1).unwrap().category, HandCategory::Straight);
+        assert_eq!(showdown_result.get_hand(1).unwrap().hand_rank, showdown_result.get_hand(2).unwrap().hand_rank);
+        assert_eq!(showdown_result.payoffs, game_state.get_payoffs());
+    }
+
+    // Three players with a small blind of 51 who folds, the other two split 251 chips with a broadway straight on the board
+    fn setup_split_pot() -> NLTHGameState {
+        let mut game_state = NLTHGameState::new_with_table(3, [10000, 10000, 10000, 0, 0, 0], 51, 100, 0);
+        game_state.private_hands = [
+            [card_from_string("7c".to_string()), card_from_string("8d".to_string())],
+            [card_from_string("2c".to_string()), card_from_string("3d".to_string())],
+            [card_from_string("4h".to_string()), card_from_string("5s".to_string())],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+        ];
+        game_state.community_cards = [
+            card_from_string("Ah".to_string()), card_from_string("Kh".to_string()),
+            card_from_string("Qd".to_string()), card_from_string("Jc".to_string()),
+            card_from_string("Ts".to_string()),
+        ];
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        while !game_state.is_terminal() {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        return game_state
+    }
+
+    #[test]
+    fn test_odd_chip_goes_left_of_the_button() {
+        let mut game_state = setup_split_pot();
+        assert_eq!(game_state.get_total_pot(), 251);
+        // The button is player 2, the small blind left of them folded so the big blind gets the odd chip
+        assert_eq!(game_state.get_payoffs(), [-51, 26, 25, 0, 0, 0]);
+
+        game_state.odd_chip_rule = OddChipRule::FirstFromSeat(2);
+        assert_eq!(game_state.get_payoffs(), [-51, 25, 26, 0, 0, 0]);
+        assert_eq!(game_state.get_odd_chip_order().collect::<Vec<usize>>(), vec![2, 0, 1]);
+    }
+
+    // Cuts the smallest remaining contribution of the players that didn't fold off every player until nobody is left
+    fn naive_pots(contributions: &[u32], folded_players: &[bool]) -> Vec<Pot> {
+        let mut remaining = contributions.to_vec();
+        let mut pots: Vec<Pot> = Vec::new();
+        while let Some(level) = (0..remaining.len()).filter(|&i| !folded_players[i] && remaining[i] > 0).map(|i| remaining[i]).min() {
+            let eligible_players = (0..remaining.len()).filter(|&i| !folded_players[i] && remaining[i] >= level).collect();
+            let amount = remaining.iter_mut().map(|chips| {
+                let pot_chips = (*chips).min(level);
+                *chips -= pot_chips;
+                return pot_chips
+            }).sum();
+            match pots.last_mut() {
+                Some(last_pot) if last_pot.eligible_players == eligible_players => last_pot.amount += amount,
+                _ => pots.push(Pot { amount, eligible_players }),
+            }
+        }
+        // Folded players that put in more than anyone else
+        if let Some(last_pot) = pots.last_mut() {
+            last_pot.amount += remaining.iter().sum::<u32>();
+        }
+        return pots
+    }
+
+    #[test]
+    fn test_pot_manager_matches_naive_side_pots() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..10_000 {
+            // At the end of a hand the players that aren't all-in put in the most, the all-in players at most that much
+            let player_amount = rng.gen_range(2..=6);
+            let highest_contribution = rng.gen_range(1..=1000);
+            let mut contributions = vec![0; player_amount];
+            let mut folded_players = vec![false; player_amount];
+            let mut all_in_players = vec![false; player_amount];
+            for player_index in 0..player_amount {
+                match rng.gen_range(0..3) {
+                    0 => contributions[player_index] = highest_contribution,
+                    1 => {
+                        all_in_players[player_index] = true;
+                        contributions[player_index] = rng.gen_range(1..=highest_contribution);
+                    },
+                    _ => {
+                        folded_players[player_index] = true;
+                        contributions[player_index] = rng.gen_range(0..=highest_contribution);
+                    },
+                }
+            }
+            if folded_players.iter().all(|&folded| folded) {
+                folded_players[0] = false;
+                contributions[0] = highest_contribution;
+            }
+
+            let pot_manager = PotManager::from_contributions(&contributions, &folded_players, &all_in_players);
+            assert_eq!(pot_manager.pots(), naive_pots(&contributions, &folded_players).as_slice(), "{:?} {:?} {:?}", contributions, folded_players, all_in_players);
+            assert_eq!(pot_manager.get_total(), contributions.iter().sum::<u32>());
+        }
+    }
+
+    #[test]
+    fn test_game_state_pots_match_naive_side_pots() {
+        // Random hands with uneven stacks, so there are all-ins for every amount
+        let mut rng = StdRng::seed_from_u64(13);
+        for _ in 0..500 {
+            let player_amount = rng.gen_range(2..=6);
+            let mut stacks = [0; 6];
+            for stack in stacks[..player_amount].iter_mut() {
+                *stack = rng.gen_range(200..=20_000);
+            }
+            let mut game_state = NLTHGameState::new_with_table(player_amount, stacks, 50, 100, rng.gen_range(0..=10));
+            loop {
+                let contributions = game_state.get_contributions();
+                assert_eq!(game_state.get_total_pot(), contributions.iter().sum::<u32>());
+                for player_index in 0..player_amount {
+                    assert_eq!(game_state.stacks[player_index] + contributions[player_index], stacks[player_index]);
+                }
+                if game_state.is_terminal() {
+                    let naive_pots = naive_pots(&contributions[..player_amount], &game_state.folded_players[..player_amount]);
+                    assert_eq!(game_state.get_pot_manager().pots(), naive_pots.as_slice(), "{:?}", game_state.history);
+                    break
+                }
+                let actions = game_state.get_active_player_actions(DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(&game_state));
+                game_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_side_pot_eligibility() {
+        let mut game_state = NLTHGameState::new_with_table(3, [1000, 10000, 10000, 0, 0, 0], 50, 100, 0);
+        game_state.private_hands = [
+            [card_from_string("As".to_string()), card_from_string("Ad".to_string())],
+            [card_from_string("Ks".to_string()), card_from_string("Kd".to_string())],
+            [card_from_string("Qs".to_string()), card_from_string("Qd".to_string())],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+            [NO_CARD_PLACEHOLDER; 2],
+        ];
+        game_state.community_cards = [
+            card_from_string("2c".to_string()), card_from_string("7d".to_string()),
+            card_from_string("9h".to_string()), card_from_string("Jc".to_string()),
+            card_from_string("4h".to_string()),
+        ];
+        // The small blind goes all-in for 1000 and the others keep betting on the flop
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+
+        let pot_manager = game_state.get_pot_manager();
+        assert_eq!(pot_manager.pots().len(), 2);
+        assert_eq!((pot_manager.pots()[0].amount, pot_manager.pots()[0].eligible_players.as_slice()), (3000, &[0, 1, 2][..]));
+        assert_eq!((pot_manager.pots()[1].amount, pot_manager.pots()[1].eligible_players.as_slice()), (3000, &[1, 2][..]));
+
+        while !game_state.is_terminal() {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        // The aces only win the main pot, the kings win the side pot
+        assert_eq!(game_state.get_payoffs(), [2000, 500, -2500, 0, 0, 0]);
+
+        let showdown_result = game_state.get_showdown_result();
+        assert_eq!(showdown_result.pots.len(), 2);
+        assert_eq!(showdown_result.pots[0].eligible_players.as_slice(), &[0, 1, 2]);
+        assert_eq!(showdown_result.pots[0].winners.as_slice(), &[(0, 3000)]);
+        assert_eq!(showdown_result.pots[1].eligible_players.as_slice(), &[1, 2]);
+        assert_eq!(showdown_result.pots[1].winners.as_slice(), &[(1, 3000)]);
+        assert_eq!(showdown_result.get_hand(0).unwrap().category, HandCategory::OnePair);
+        assert!(showdown_result.get_hand(0).unwrap().hand_rank > showdown_result.get_hand(1).unwrap().hand_rank);
+        assert_eq!(showdown_result.get_amount_won(0), 3000);
+    }
+
+    #[test]
+    fn test_kuhn_and_leduc_showdown_result() {
+        let mut game_state = KPGameState::new_empty(2, false, None);
+        game_state.private_hands[0][0] = card_from_string("Kh".to_string());
+        game_state.private_hands[1][0] = card_from_string("Jh".to_string());
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        let showdown_result = game_state.get_showdown_result();
+        assert_eq!(showdown_result.pots[0].eligible_players.as_slice(), &[0, 1]);
+        assert_eq!(showdown_result.pots[0].winners.as_slice(), &[(0, 200)]);
+        assert_eq!(showdown_result.hands.len(), 2);
+        assert_eq!(showdown_result.payoffs, game_state.get_payoffs());
+
+        let mut game_state = LPGameState::new_empty(2, false, None);
+        game_state.private_hands[0][0] = card_from_string("Qh".to_string());
+        game_state.private_hands[1][0] = card_from_string("Kh".to_string());
+        game_state.community_cards[0] = card_from_string("Qs".to_string());
+        while !game_state.is_terminal() {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        let showdown_result = game_state.get_showdown_result();
+        // The pair of queens beats the king
+        assert_eq!(showdown_result.get_hand(0).unwrap().category, HandCategory::OnePair);
+        assert_eq!(showdown_result.get_hand(1).unwrap().category, HandCategory::HighCard);
+        assert!(showdown_result.get_hand(0).unwrap().hand_rank > showdown_result.get_hand(1).unwrap().hand_rank);
+        assert_eq!(showdown_result.pots[0].winners[0].0, 0);
+        assert_eq!(showdown_result.get_amount_won(0), showdown_result.pots[0].amount);
+        assert_eq!(showdown_result.payoffs, game_state.get_payoffs());
+    }
+
+    // Plays a heads-up hand to the end by calling, or folds on the flop
+    fn setup_heads_up_terminal(fold: bool) -> NLTHGameState {
+        let mut game_state = NLTHGameState::new_with_table(2, [10000, 3000, 0, 0, 0, 0], 50, 100, 0);
+        game_state.community_cards = [
+            card_from_string("Kh".to_string()), card_from_string("7d".to_string()),
+            card_from_string("2c".to_string()), card_from_string("9s".to_string()),
+            card_from_string("Ts".to_string()),
+        ];
+        game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 400, sizing: BetSizing::BigBlinds });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        if fold {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Bet, raise_amount: 50, sizing: BetSizing::PotFraction });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Fold, raise_amount: 0, sizing: BetSizing::PotFraction });
+        } else {
+            game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        return game_state
+    }
+
+    // Plays every pair of combos that can be dealt together, the way the range payoffs should add up
+    fn naive_range_payoffs(game_state: &NLTHGameState, player_index: usize, opponent_range: &[f32; HOLE_CARD_COMBOS]) -> Vec<f32> {
+        // A showdown uses the whole board, after a fold only the cards of the rounds that were reached are seen
+        let board_card_amount = if game_state.folded_players.contains(&true) { BOARD_CARD_AMOUNTS[game_state.round] } else { COMMUNITY_CARD_AMOUNT };
+        let board = &game_state.community_cards[..board_card_amount];
+        let mut payoffs = vec![0.0; HOLE_CARD_COMBOS];
+        // Every eleventh combo of the player keeps it fast enough
+        for combo_index in (0..HOLE_CARD_COMBOS).step_by(11) {
+            let hole_cards = get_hole_card_combo(combo_index);
+            for (opponent_combo_index, &opponent_weight) in opponent_range.iter().enumerate() {
+                let opponent_hole_cards = get_hole_card_combo(opponent_combo_index);
+                let cards = [hole_cards, opponent_hole_cards].concat();
+                if cards.iter().any(|card| board.contains(card)) || opponent_hole_cards.iter().any(|card| hole_cards.contains(card)) {
+                    continue
+                }
+                let mut dealt_game_state = game_state.clone();
+                dealt_game_state.private_hands[player_index] = hole_cards;
+                dealt_game_state.private_hands[1 - player_index] = opponent_hole_cards;
+                payoffs[combo_index] += opponent_weight * dealt_game_state.get_payoffs()[player_index] as f32;
+            }
+        }
+        return payoffs
+    }
+
+    #[test]
+    fn test_range_payoffs_match_naive_payoffs() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut opponent_range = [0.0; HOLE_CARD_COMBOS];
+        // A sparse range keeps the naive version fast
+        for _ in 0..40 {
+            opponent_range[rng.gen_range(0..HOLE_CARD_COMBOS)] = rng.gen_range(0.0..1.0);
+        }
+
+        for fold in [false, true] {
+            let game_state = setup_heads_up_terminal(fold);
+            for player_index in 0..2 {
+                let payoffs = match fold {
+                    true => fold_payoffs(&game_state, player_index, &opponent_range).unwrap(),
+                    false => showdown_payoffs(&game_state, player_index, &opponent_range).unwrap(),
+                };
+                let naive_payoffs = naive_range_payoffs(&game_state, player_index, &opponent_range);
+                for combo_index in (0..HOLE_CARD_COMBOS).step_by(11) {
+                    assert!((payoffs[combo_index] - naive_payoffs[combo_index]).abs() <= 1e-4 * naive_payoffs[combo_index].abs().max(1.0), "{} {}", payoffs[combo_index], naive_payoffs[combo_index]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_showdown_payoffs_card_removal() {
+        let game_state = setup_heads_up_terminal(false);
+        let hand = |cards: &str| cards.split_whitespace().map(|card| card_from_string(card.to_string())).collect::<Vec<u8>>();
+        let combo = |cards: &str| get_combo_index(hand(cards)[0], hand(cards)[1]);
+
+        // The opponent only holds a set of nines, which the player holding a nine can't be up against
+        let mut opponent_range = [0.0; HOLE_CARD_COMBOS];
+        opponent_range[combo("9h 9d")] = 1.0;
+        let payoffs = showdown_payoffs(&game_state, 0, &opponent_range).unwrap();
+        assert_eq!(payoffs[combo("Ah Kd")], -3000.0);
+        assert_eq!(payoffs[combo("9h Ac")], 0.0);
+        assert_eq!(payoffs[combo("Jh Qh")], 3000.0);
+        assert!(rank_hand(&[hand("Jh Qh"), hand("Kh 7d 2c 9s Ts")].concat()) > rank_hand(&[hand("9h 9d"), hand("Kh 7d 2c 9s Ts")].concat()));
+        // Combos that share a card with the board can't be dealt
+        assert_eq!(payoffs[combo("Kh Ks")], 0.0);
+
+        assert!(fold_payoffs(&game_state, 0, &opponent_range).is_err());
+        assert!(showdown_payoffs(&setup_heads_up_terminal(true), 0, &opponent_range).is_err());
+    }
+
+    #[test]
+    fn test_fold_payoffs_only_remove_dealt_cards() {
+        // The fold happens on the flop, the turn and river are dealt up front but nobody saw them
+        let game_state = setup_heads_up_terminal(true);
+        assert_eq!(game_state.round, 1);
+        let hand = |cards: &str| cards.split_whitespace().map(|card| card_from_string(card.to_string())).collect::<Vec<u8>>();
+        let combo = |cards: &str| get_combo_index(hand(cards)[0], hand(cards)[1]);
+
+        let mut opponent_range = [0.0; HOLE_CARD_COMBOS];
+        for opponent_combo in ["Qd Qc", "8d 8c", "Ts 8h"] {
+            opponent_range[combo(opponent_combo)] = 1.0;
+        }
+        let payoffs = fold_payoffs(&game_state, 0, &opponent_range).unwrap();
+        let payoff = game_state.get_payoffs()[0] as f32;
+        // The opponent can hold the river card
+        assert_eq!(payoffs[combo("9h Jh")], 3.0 * payoff);
+        // So can the player, which only blocks the opponent combo that holds it too
+        assert_eq!(payoffs[combo("Ts Jh")], 2.0 * payoff);
+        // A combo holding a flop card can't be dealt
+        assert_eq!(payoffs[combo("Kh Jh")], 0.0);
+        assert_eq!(fold_payoffs(&game_state, 1, &opponent_range).unwrap()[combo("9h Jh")], -3.0 * payoff);
+    }
+
+    #[test]
+    fn test_showdown_payoffs_need_the_whole_board() {
+        // A preflop all-in of a public tree, where no cards are dealt
+        let mut game_state = NLTHGameState::new_empty(2, false, None);
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.is_terminal());
+        let opponent_range = [1.0; HOLE_CARD_COMBOS];
+        assert!(showdown_payoffs(&game_state, 0, &opponent_range).is_err());
+
+        // Or a flop all-in where only the flop is known
+        game_state.community_cards[..3].copy_from_slice(&[card_from_string("2c".to_string()), card_from_string("7d".to_string()), card_from_string("9h".to_string())]);
+        assert!(showdown_payoffs(&game_state, 1, &opponent_range).is_err());
+    }
+
+    #[test]
+    fn test_expected_payoffs_on_the_turn() {
+        let mut game_state = NLTHGameState::new_with_table(2, [1000, 1000, 0, 0, 0, 0], 50, 100, 0);
+        game_state.private_hands[0] = [card_from_string("Ah".to_string()), card_from_string("Ad".to_string())];
+        game_state.private_hands[1] = [card_from_string("Kh".to_string()), card_from_string("Kd".to_string())];
+        game_state.community_cards = [
+            card_from_string("2c".to_string()), card_from_string("7s".to_string()),
+            card_from_string("9d".to_string()), card_from_string("Ts".to_string()),
+            card_from_string("Kc".to_string()),
+        ];
+        // Checked down to the turn, where both players are all-in
+        for _ in 0..4 {
+            game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        }
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.is_terminal());
+
+        // The king on the river hands the pot to the kings, on average the aces win it
+        assert_eq!(game_state.get_payoffs()[..2], [-1000, 1000]);
+        let expected_payoffs = game_state.expected_payoffs(1000, None).unwrap();
+        assert!((expected_payoffs[0] - 1000.0 * 40.0 / 44.0).abs() < 1e-9);
+        assert!((expected_payoffs[0] + expected_payoffs[1]).abs() < 1e-9);
+
+        let equity_result = calculate_equity(
+            &[HoleCards::Hand(game_state.private_hands[0]), HoleCards::Hand(game_state.private_hands[1])],
+            &game_state.community_cards[..4], &[], 1000, None
+        ).unwrap();
+        assert!((expected_payoffs[0] - (equity_result.players[0].equity * 2000.0 - 1000.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_expected_payoffs_with_side_pots() {
+        let mut game_state = NLTHGameState::new_with_table(3, [1000, 3000, 5000, 0, 0, 0], 50, 100, 0);
+        game_state.private_hands[..3].copy_from_slice(&[
+            [card_from_string("As".to_string()), card_from_string("Ad".to_string())],
+            [card_from_string("Ks".to_string()), card_from_string("Kd".to_string())],
+            [card_from_string("7c".to_string()), card_from_string("8c".to_string())],
+        ]);
+        game_state.community_cards = [
+            card_from_string("2c".to_string()), card_from_string("7d".to_string()),
+            card_from_string("9h".to_string()), card_from_string("Jc".to_string()),
+            card_from_string("4h".to_string()),
+        ];
+        // Everyone is all-in preflop for a different amount
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        game_state = game_state.handle_action(Action { action_type: ActionType::AllIn, raise_amount: 0, sizing: BetSizing::PotFraction });
+        assert!(game_state.is_terminal());
+
+        let expected_payoffs = game_state.expected_payoffs(500, Some(7)).unwrap();
+        assert_eq!(game_state.expected_payoffs(500, Some(7)).unwrap(), expected_payoffs);
+        assert!(expected_payoffs.iter().sum::<f64>().abs() < 1e-6);
+        // The aces can win at most 1000 from each of the others and the biggest stack gets its last 2000 chips back
+        assert!(expected_payoffs[0] > 0.0 && expected_payoffs[0] <= 2000.0);
+        assert!(expected_payoffs[2] >= -3000.0);
+
+        // Once all the cards are out there is a single outcome
+        let river_state = setup_split_pot();
+        assert_eq!(river_state.expected_payoffs(1, None).unwrap(), river_state.get_payoffs().map(|payoff| payoff as f64));
+    }
+}
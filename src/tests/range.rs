@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod range_tests {
+    use hand_isomorphism_rust::deck::{card_from_string, Card};
+
+    use crate::constants::HOLE_CARD_COMBOS;
+    use crate::game_states::nlth_poker::rank::{get_combo_index, get_hole_card_combo};
+    use crate::range::{get_preflop_class, get_preflop_class_name, Range, PREFLOP_CLASSES};
+
+    fn hand(hand: &str) -> [Card; 2] {
+        let cards = hand.split_whitespace().map(|card| card_from_string(card.to_string())).collect::<Vec<Card>>();
+        return cards.try_into().unwrap()
+    }
+
+    fn weight(range: &Range, hand_string: &str) -> f32 {
+        let [card_a, card_b] = hand(hand_string);
+        return range.weights[get_combo_index(card_a, card_b)]
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let range = Range::parse("22+, A2s+, KTo+, QJs").unwrap();
+        // 13 pairs, 12 suited aces, 3 offsuit kings and QJs
+        assert_eq!(range.get_combos().len(), 13 * 6 + 12 * 4 + 3 * 12 + 4);
+        assert_eq!(weight(&range, "2c 2d"), 1.0);
+        assert_eq!(weight(&range, "Ah Kh"), 1.0);
+        assert_eq!(weight(&range, "Ah Kd"), 0.0);
+        assert_eq!(weight(&range, "Kh Td"), 1.0);
+        assert_eq!(weight(&range, "Kh Th"), 0.0);
+        assert_eq!(weight(&range, "Ks 9d"), 0.0);
+        assert_eq!(weight(&range, "Qs Js"), 1.0);
+
+        assert_eq!(Range::parse("22-44").unwrap().get_combos().len(), 18);
+        assert_eq!(Range::parse("A5s-A2s").unwrap().get_combos().len(), 16);
+        assert_eq!(Range::parse("AK").unwrap().get_combos().len(), 16);
+        assert_eq!(Range::parse("KA").unwrap(), Range::parse("AK").unwrap());
+        assert_eq!(Range::parse("").unwrap().get_combos().len(), 0);
+        let combo_range = Range::parse("AhKd").unwrap();
+        assert_eq!(combo_range.get_combos(), vec![(get_hole_card_combo(get_combo_index(card_from_string("Ah".to_string()), card_from_string("Kd".to_string()))), 1.0)]);
+        // Ranks may be lowercase, like in classes
+        assert_eq!(Range::parse("ahkd").unwrap(), combo_range);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        // Later parts override earlier ones
+        let range = Range::parse("AK, AKs:0.5, QQ+:0.25, AhAd:1").unwrap();
+        assert_eq!(weight(&range, "Ah Kh"), 0.5);
+        assert_eq!(weight(&range, "Ah Kd"), 1.0);
+        assert_eq!(weight(&range, "Qh Qd"), 0.25);
+        assert_eq!(weight(&range, "Ah Ad"), 1.0);
+        assert_eq!(weight(&range, "Ah As"), 0.25);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for notation in ["AA+s", "XY", "AKs:x", "AKs:2", "AAs", "A2s-K2s", "A2s-A5o", "AhAh", "AKQ", "XhKd", "AhKx", "AhK1"] {
+            assert!(Range::parse(notation).is_err(), "{} should not parse", notation);
+        }
+    }
+
+    #[test]
+    fn test_preflop_classes() {
+        assert_eq!(get_preflop_class(hand("Ah As")), 0);
+        assert_eq!(get_preflop_class(hand("Ah Kh")), 1);
+        assert_eq!(get_preflop_class(hand("Kd Ad")), 1);
+        assert_eq!(get_preflop_class(hand("Ah Kd")), 13);
+        assert_eq!(get_preflop_class(hand("2c 2d")), 168);
+        assert_eq!(get_preflop_class_name(0), "AA");
+        assert_eq!(get_preflop_class_name(1), "AKs");
+        assert_eq!(get_preflop_class_name(13), "AKo");
+        assert_eq!(get_preflop_class_name(get_preflop_class(hand("7c 2d"))), "72o");
+
+        // Every class is used, by 6 pairs, 4 suited or 12 offsuit combos
+        let mut combo_amounts = [0; PREFLOP_CLASSES];
+        for combo_index in 0..HOLE_CARD_COMBOS {
+            combo_amounts[get_preflop_class(get_hole_card_combo(combo_index))] += 1;
+        }
+        for (preflop_class, &combo_amount) in combo_amounts.iter().enumerate() {
+            let name = get_preflop_class_name(preflop_class);
+            let expected_amount = if name.ends_with('s') { 4 } else if name.ends_with('o') { 12 } else { 6 };
+            assert_eq!(combo_amount, expected_amount, "{}", name);
+            assert_eq!(Range::parse(&name).unwrap().get_combos().len(), combo_amount);
+        }
+    }
+
+    #[test]
+    fn test_remove_blocked() {
+        let mut range = Range::parse("AKs, QQ").unwrap();
+        range.remove_blocked(&hand("Ah Qd"));
+        assert_eq!(range.get_combos().len(), 3 + 3);
+        assert_eq!(weight(&range, "Ah Kh"), 0.0);
+        assert_eq!(weight(&range, "Qh Qs"), 1.0);
+        assert_eq!(range.get_preflop_class_weight(1), 0.75);
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Range::parse("AA, AKs:0.5, KK").unwrap().to_grid();
+        let lines = grid.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0].split_whitespace().collect::<Vec<&str>>()[..3], ["AA", "AKs:0.50", "."]);
+        assert_eq!(lines[1].split_whitespace().collect::<Vec<&str>>()[..3], [".", "KK", "."]);
+        assert!(lines[2..].iter().all(|line| line.split_whitespace().all(|cell| cell == ".")));
+    }
+}
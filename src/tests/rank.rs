@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod rank_tests {
+    use hand_isomorphism_rust::deck::{card_from_string, card_to_string};
+
+    use crate::constants::{HOLE_CARD_COMBOS, NO_CARD_PLACEHOLDER};
+    use crate::game_states::nlth_poker::rank::{describe_hand, get_combo_index, get_hand_category, get_hole_card_combo, rank_hand, rank_hole_card_combos, HandCategory, BLOCKED_HAND_RANK};
+
+    fn cards(hand: &str) -> Vec<u8> {
+        return hand.split_whitespace().map(|card| card_from_string(card.to_string())).collect()
+    }
+
+    fn best_cards(hand: &str) -> String {
+        return describe_hand(&cards(hand)).unwrap().best_cards.iter().map(|&card| card_to_string(card)).collect::<Vec<String>>().join(" ")
+    }
+
+    #[test]
+    fn test_describe_hand() {
+        let test_cases = [
+            ("As Kd 9h 7c 4s 3d 2h", HandCategory::HighCard, "High card, Ace, King-Nine-Seven-Four kickers"),
+            ("Kh Kd 9h 7c 4s 3d Ah", HandCategory::OnePair, "Pair of Kings, Ace-Nine-Seven kickers"),
+            ("Kh Kd 7h 7c 4s 4d Ah", HandCategory::TwoPair, "Two pair, Kings and Sevens, Ace kicker"),
+            ("6h 6d 6s Qc 4s 3d 2h", HandCategory::ThreeOfAKind, "Three of a kind, Sixes, Queen-Four kickers"),
+            ("Ah 2d 3s 4c 5s Kd Kh", HandCategory::Straight, "Straight, Five high"),
+            ("Ah 9h 3h 4h 5h 6d 2s", HandCategory::Flush, "Flush, Ace-Nine-Five-Four-Three"),
+            ("Kh Kd Ks 7c 7s 7d 2h", HandCategory::FullHouse, "Full house, Kings full of Sevens"),
+            ("7h 7d 7s 7c Ks Ad 2h", HandCategory::FourOfAKind, "Four of a kind, Sevens, Ace kicker"),
+            ("9h 8h 7h 6h 5h 4h Ah", HandCategory::StraightFlush, "Straight flush, Nine high"),
+            ("Th Jh Qh Kh Ah", HandCategory::StraightFlush, "Royal flush"),
+        ];
+        for (hand, category, description) in test_cases {
+            let hand_description = describe_hand(&cards(hand)).unwrap();
+            assert_eq!(hand_description.category, category, "{}", hand);
+            assert_eq!(hand_description.description, description);
+        }
+    }
+
+    #[test]
+    fn test_best_cards() {
+        // The made hand comes first, then the kickers
+        assert_eq!(best_cards("Kh 7d Kd 7c 4s 4d Ah"), "Kh Kd 7d 7c Ah");
+        assert_eq!(best_cards("Ah 2d 3s 4c 5s Kd Kh"), "5s 4c 3s 2d Ah");
+        assert!(describe_hand(&cards("Ah Kh Qh Jh")).is_err());
+        assert!(describe_hand(&cards("Ah Kh Qh Jh Ah")).is_err());
+        assert!(describe_hand(&[cards("Ah Kh Qh Jh").as_slice(), &[NO_CARD_PLACEHOLDER]].concat()).is_err());
+        assert!(describe_hand(&[cards("Ah Kh Qh Jh").as_slice(), &[60]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_combo_index_round_trip() {
+        for combo_index in 0..HOLE_CARD_COMBOS {
+            let [card_a, card_b] = get_hole_card_combo(combo_index);
+            assert!(card_a < card_b);
+            assert_eq!(get_combo_index(card_a, card_b), combo_index);
+            assert_eq!(get_combo_index(card_b, card_a), combo_index);
+        }
+    }
+
+    #[test]
+    fn test_rank_hole_card_combos() {
+        let board = cards("Kh 7d 2c 9s Ts");
+        let hand_ranks = rank_hole_card_combos(&board.clone().try_into().unwrap());
+
+        // 47 * 46 / 2 combos don't share a card with the board
+        assert_eq!(hand_ranks.iter().filter(|&&hand_rank| hand_rank != BLOCKED_HAND_RANK).count(), 1081);
+        for (combo_index, &hand_rank) in hand_ranks.iter().enumerate() {
+            let hole_cards = get_hole_card_combo(combo_index);
+            if board.contains(&hole_cards[0]) || board.contains(&hole_cards[1]) {
+                assert_eq!(hand_rank, BLOCKED_HAND_RANK);
+                continue
+            }
+            let mut hand = board.clone();
+            hand.extend(hole_cards);
+            assert_eq!(hand_rank, rank_hand(&hand));
+        }
+        assert_eq!(get_hand_category(hand_ranks[get_combo_index(card_from_string("Jc".to_string()), card_from_string("Qd".to_string()))]), HandCategory::Straight);
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod serialization_tests {
+    use std::fmt::Debug;
+
+    use rand::prelude::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use smallvec::SmallVec;
+
+    use crate::game_states::base_game_state::GameState;
+    use crate::game_states::kuhn_poker::game_state::KPGameState;
+    use crate::game_states::leduc_poker::game_state::LPGameState;
+    use crate::game_states::nlth_poker::game_state::NLTHGameState;
+    use crate::game_states::serialization::{game_state_from_json, game_state_to_json};
+    use crate::structs::{Action, ActionType, BetSizing};
+    use crate::action_abstraction::DEFAULT_ACTION_ABSTRACTION;
+
+    // Plays random games and checks at every step that a deserialized copy is identical and handles every legal action identically
+    fn assert_round_trips<T, F>(player_amount: usize, bets_in_abstraction: F)
+    where
+        T: GameState + Serialize + DeserializeOwned + Debug,
+        F: Fn(&T) -> Option<&SmallVec<[Action; 40]>>,
+    {
+        let mut rng = StdRng::seed_from_u64(7);
+        for seed in 0..20 {
+            let mut game_state = T::new_empty(player_amount, true, Some(seed));
+            while !game_state.is_terminal() {
+                let copy = game_state_from_json::<T>(&game_state_to_json(&game_state)).unwrap();
+                assert_eq!(format!("{:?}", copy), format!("{:?}", game_state));
+
+                let actions = game_state.get_active_player_actions(bets_in_abstraction(&game_state));
+                assert_eq!(copy.get_active_player_actions(bets_in_abstraction(&copy)), actions);
+                for &action in actions.iter() {
+                    assert_eq!(format!("{:?}", copy.handle_action(action)), format!("{:?}", game_state.handle_action(action)));
+                }
+
+                game_state = game_state.handle_action(*actions.choose(&mut rng).unwrap());
+            }
+
+            let copy = game_state_from_json::<T>(&game_state_to_json(&game_state)).unwrap();
+            assert!(copy.is_terminal());
+            assert_eq!(copy.get_payoffs(), game_state.get_payoffs());
+        }
+    }
+
+    #[test]
+    fn test_nlth_round_trip() {
+        for player_amount in [2, 3, 6] {
+            assert_round_trips::<NLTHGameState, _>(player_amount, |game_state| {
+                DEFAULT_ACTION_ABSTRACTION.get_bets_in_abstraction(game_state)
+            });
+        }
+    }
+
+    #[test]
+    fn test_leduc_round_trip() {
+        assert_round_trips::<LPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_kuhn_round_trip() {
+        assert_round_trips::<KPGameState, _>(2, |_| None);
+    }
+
+    #[test]
+    fn test_version_is_checked() {
+        let json = game_state_to_json(&KPGameState::new_empty(2, true, Some(1)));
+        assert!(json.starts_with("{\"version\":2,"));
+        assert!(game_state_from_json::<KPGameState>(&json.replacen("\"version\":2", "\"version\":1", 1)).is_err());
+        assert!(game_state_from_json::<KPGameState>("{}").is_err());
+    }
+
+    #[test]
+    fn test_defaulted_fields_need_no_version_bump() {
+        // A file from before odd_chip_rule, players_acted and bet_rounding existed loads into the same state
+        // At the start of the flop nobody acted yet, so players_acted is the default as well
+        let call = Action { action_type: ActionType::Call, raise_amount: 0, sizing: BetSizing::PotFraction };
+        let game_state = NLTHGameState::new_empty(2, true, Some(1)).handle_action(call).handle_action(call);
+        assert_eq!(game_state.round, 1);
+        let mut json = serde_json::from_str::<serde_json::Value>(&game_state_to_json(&game_state)).unwrap();
+        for field in ["odd_chip_rule", "players_acted", "bet_rounding"] {
+            assert!(json["game_state"].as_object_mut().unwrap().remove(field).is_some());
+        }
+        let copy = game_state_from_json::<NLTHGameState>(&json.to_string()).unwrap();
+        assert_eq!(format!("{:?}", copy), format!("{:?}", game_state));
+    }
+}